@@ -0,0 +1,113 @@
+use std::collections::BTreeMap;
+use crate::registry::Registry;
+use crate::scorecard::Scorecard;
+use crate::standings::standings;
+use crate::tiebreak::TieBreaker;
+
+/// The totals and ranks of every bowler under every registered variant
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Comparison {
+    /// Variant names in registry order, which is the order of the results of every bowler
+    pub variants: Vec<String>,
+    /// Bowlers in name order
+    pub bowlers: Vec<BowlerComparison>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BowlerComparison {
+    pub name: String,
+    pub results: Vec<VariantResult>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VariantResult {
+    pub total: u32,
+    pub rank: usize,
+}
+
+/// A change of winner from one variant to the next
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WinnerChange {
+    pub from_variant: String,
+    pub from_winners: Vec<String>,
+    pub to_variant: String,
+    pub to_winners: Vec<String>,
+}
+
+/// Score the scorecards under every variant in the registry
+pub fn compare(scorecards: &[Scorecard], registry: &Registry, tie_breakers: &[&dyn TieBreaker]) -> Comparison {
+    let mut bowlers: BTreeMap<String, Vec<VariantResult>> = BTreeMap::new();
+    for entry in registry.entries() {
+        for standing in standings(scorecards, entry.variant.as_ref(), tie_breakers) {
+            bowlers.entry(standing.name)
+                .or_default()
+                .push(VariantResult { total: standing.total, rank: standing.rank });
+        }
+    }
+    Comparison {
+        variants: registry.entries().iter().map(|entry| entry.name.clone()).collect(),
+        bowlers: bowlers.into_iter()
+            .map(|(name, results)| BowlerComparison { name, results })
+            .collect(),
+    }
+}
+
+impl Comparison {
+    /// The bowlers ranked first under the variant at the index, several if they are tied
+    pub fn winners(&self, variant: usize) -> Vec<String> {
+        self.bowlers.iter()
+            .filter(|bowler| bowler.results[variant].rank == 1)
+            .map(|bowler| bowler.name.clone())
+            .collect()
+    }
+
+    /// Every place where the next variant in the registry has a different winner than the one before
+    pub fn winner_changes(&self) -> Vec<WinnerChange> {
+        (1..self.variants.len())
+            .map(|variant| (variant, self.winners(variant - 1), self.winners(variant)))
+            .filter(|(_, from_winners, to_winners)| from_winners != to_winners)
+            .map(|(variant, from_winners, to_winners)| WinnerChange {
+                from_variant: self.variants[variant - 1].clone(),
+                from_winners,
+                to_variant: self.variants[variant].clone(),
+                to_winners,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::frame::GameRules;
+    use crate::registry::Registry;
+    use crate::scorecard::{Notation, Scorecard};
+    use super::{compare, VariantResult, WinnerChange};
+
+    #[test]
+    fn test_compare() {
+        // Given a scorecard where variant 1 and variant 2 disagree on the winner
+        let scorecards = [Scorecard::parse("monday.txt", "\
+            Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3\n\
+            Eve Stojbs 3 7 3 3 9 1 6 4 2 3 1 0\n\
+            ", Notation::Numeric, GameRules::default()).unwrap()];
+
+        // When the variants are compared
+        let comparison = compare(&scorecards, &Registry::default(), &[]);
+
+        // Then every bowler has a total and rank under every variant
+        assert_eq!(comparison.variants, ["variant1", "variant2", "variant3", "variant4", "variant5"]);
+        let result = |total, rank| VariantResult { total, rank };
+        assert_eq!(comparison.bowlers[0].name, "Eve Stojbs");
+        assert_eq!(comparison.bowlers[0].results, [result(42, 2), result(57, 1), result(60, 1), result(53, 1), result(212, 1)]);
+        assert_eq!(comparison.bowlers[1].name, "Yattas Del Lana");
+        assert_eq!(comparison.bowlers[1].results, [result(45, 1), result(55, 2), result(55, 2), result(52, 2), result(207, 2)]);
+
+        // And the change of winner is found
+        assert_eq!(comparison.winner_changes(), [WinnerChange {
+            from_variant: "variant1".to_owned(),
+            from_winners: vec!["Yattas Del Lana".to_owned()],
+            to_variant: "variant2".to_owned(),
+            to_winners: vec!["Eve Stojbs".to_owned()],
+        }]);
+    }
+}
@@ -0,0 +1,18 @@
+use std::fs;
+use std::path::Path;
+use anyhow::{anyhow, Context, Result};
+use serde::de::DeserializeOwned;
+
+/// Read a TOML or JSON file, picking the format from the `.toml` or `.json` extension. The kind of file, e.g.
+/// `rule`, names it in errors.
+pub fn load_config<T: DeserializeOwned>(kind: &str, path: impl AsRef<Path>) -> Result<T> {
+    let path = path.as_ref();
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Could not read {} file {}", kind, path.display()))?;
+    let config = match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) if extension.eq_ignore_ascii_case("toml") => toml::from_str(&contents).map_err(Into::into),
+        Some(extension) if extension.eq_ignore_ascii_case("json") => serde_json::from_str(&contents).map_err(Into::into),
+        _ => Err(anyhow!("Unknown {} file format, expected .toml or .json", kind)),
+    };
+    config.with_context(|| format!("Invalid {} file {}", kind, path.display()))
+}
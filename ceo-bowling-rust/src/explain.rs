@@ -0,0 +1,146 @@
+use std::fmt::{self, Display, Formatter};
+use itertools::Itertools;
+use crate::frame::{Frame, TenthFrame};
+use crate::variants::{BonusSource, FrameScore, ScoreCalculator};
+
+/// How a variant scored a series, frame by frame, and how the variants it adds up contributed to that
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Explanation {
+    /// The name of the variant, prefixed by the names of the variants it is part of, e.g. `variant5/variant2`
+    pub name: String,
+    pub total: u32,
+    pub frames: Vec<FrameExplanation>,
+    pub parts: Vec<Explanation>,
+}
+
+/// A frame score together with the rolls its bonus came from, if it came from any
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FrameExplanation {
+    pub score: FrameScore,
+    pub bonus_rolls: Vec<u8>,
+}
+
+pub fn explain(name: &str, series: &[Frame], variant: &dyn ScoreCalculator) -> Explanation {
+    let frame_scores = variant.score_frames(series);
+    Explanation {
+        name: name.to_owned(),
+        total: frame_scores.last().map_or(0, |frame_score| frame_score.total),
+        frames: frame_scores.into_iter()
+            .enumerate()
+            .map(|(index, score)| FrameExplanation {
+                bonus_rolls: match score.bonus_source {
+                    BonusSource::NextRolls(count) => following_rolls(series, index).into_iter().take(count).collect(),
+                    _ => Vec::new(),
+                },
+                score,
+            })
+            .collect(),
+        parts: variant.parts().into_iter()
+            .map(|(part_name, part)| explain(&format!("{}/{}", name, part_name), series, part))
+            .collect(),
+    }
+}
+
+/// The rolls after the frame, which for the tenth frame are its fill balls
+fn following_rolls(series: &[Frame], index: usize) -> Vec<u8> {
+    match series[index] {
+        Frame::Tenth(TenthFrame::Strike(first_fill, second_fill)) => vec![first_fill, second_fill],
+        Frame::Tenth(TenthFrame::Spare(_, fill)) => vec![fill],
+        _ => series[index + 1..].iter().flat_map(|frame| frame.rolls()).collect(),
+    }
+}
+
+impl FrameExplanation {
+    /// What the bonus was earned for, e.g. `strike bonus` or `next 2 rolls 7 + 3`
+    pub fn bonus_reason(&self) -> String {
+        match self.score.bonus_source {
+            BonusSource::None => String::new(),
+            BonusSource::Fixed => match self.score.frame {
+                Frame::Strike | Frame::Tenth(TenthFrame::Strike(_, _)) => "strike bonus".to_owned(),
+                _ => "spare bonus".to_owned(),
+            },
+            BonusSource::NextRolls(count) => format!("next {} {} {}",
+                                                     count,
+                                                     if count == 1 { "roll" } else { "rolls" },
+                                                     self.bonus_rolls.iter().join(" + ")),
+            BonusSource::Other => "bonus".to_owned(),
+        }
+    }
+}
+
+impl Display for Explanation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if !self.parts.is_empty() {
+            writeln!(f, "{}: {} = {}", self.name, self.total, self.parts.iter()
+                .map(|part| format!("{} {}", part.name.rsplit('/').next().unwrap_or_default(), part.total))
+                .join(" + "))?;
+            for part in &self.parts {
+                writeln!(f)?;
+                write!(f, "{}", part)?;
+            }
+            return Ok(());
+        }
+        writeln!(f, "{}: {}", self.name, self.total)?;
+        writeln!(f, "{:>5}  {:>5}  {:>4}  {:>5}  {:>6}  {:>5}", "Frame", "Rolls", "Pins", "Bonus", "Points", "Total")?;
+        for (number, frame) in self.frames.iter().enumerate() {
+            let score = &frame.score;
+            let row = format!("{:>5}  {:>5}  {:>4}  {:>5}  {:>6}  {:>5}  {}",
+                              number + 1,
+                              score.frame.to_string(),
+                              score.base,
+                              score.bonus,
+                              score.base + score.bonus,
+                              score.total,
+                              frame.bonus_reason());
+            writeln!(f, "{}", row.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::frame::GameRules;
+    use crate::registry::Registry;
+    use crate::scorecard::{parse_line, Notation};
+    use crate::variants::{Variant4, Variant5};
+    use super::explain;
+
+    #[test]
+    fn test_explain_variant5() {
+        // Given a series scored with variant 5
+        let (_, series) = parse_line("Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3", Notation::Numeric, GameRules::default()).unwrap();
+
+        // When it is explained
+        let explanation = explain("variant5", &series, &Variant5::default());
+
+        // Then the total is broken down into the four variants it adds up
+        assert_eq!(explanation.total, 207);
+        let parts = explanation.parts.iter()
+            .map(|part| (part.name.as_str(), part.total))
+            .collect::<Vec<_>>();
+        assert_eq!(parts, [("variant5/variant1", 45), ("variant5/variant2", 55), ("variant5/variant3", 55), ("variant5/variant4", 52)]);
+        assert!(explanation.to_string().starts_with("variant5: 207 = variant1 45 + variant2 55 + variant3 55 + variant4 52\n"));
+    }
+
+    #[test]
+    fn test_explain_bonus_reasons() {
+        let registry = Registry::default();
+        let (_, series) = parse_line("Eve Stojbs 10 10 7 3 4 0", Notation::Numeric, GameRules::default()).unwrap();
+        for (variant_name, expected_bonuses) in [
+            ("variant3", [(10, "strike bonus"), (12, "strike bonus"), (5, "spare bonus"), (0, "")]),
+            ("variant4", [(17, "next 2 rolls 10 + 7"), (10, "next 2 rolls 7 + 3"), (4, "next 1 roll 4"), (0, "")]),
+        ] {
+            let explanation = explain(variant_name, &series, registry.variant(variant_name).unwrap().as_ref());
+            let bonuses = explanation.frames.iter()
+                .map(|frame| (frame.score.bonus, frame.bonus_reason()))
+                .collect::<Vec<_>>();
+            assert_eq!(bonuses, expected_bonuses.map(|(bonus, reason)| (bonus, reason.to_owned())), "{}", variant_name);
+        }
+
+        // Including the fill balls of the tenth frame
+        let (_, series) = parse_line("Perfect Game 10 10 10 10 10 10 10 10 10 10 10 10", Notation::Numeric, GameRules::default()).unwrap();
+        let explanation = explain("variant4", &series, &Variant4::default());
+        assert_eq!(explanation.frames[9].bonus_reason(), "next 2 rolls 10 + 10");
+    }
+}
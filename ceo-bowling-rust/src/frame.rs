@@ -0,0 +1,201 @@
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use anyhow::{bail, Error, Result};
+
+pub const FRAMES_PER_GAME: usize = 10;
+
+/// How many pins make a strike or a spare. A strike or spare counts as all ten pins however many it took, so every
+/// variant scores it the same way.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GameRules {
+    /// Pins on the first ball of a rack that make a strike
+    pub strike: u8,
+    /// Pins on both balls of a rack that make a spare
+    pub spare: u8,
+}
+
+impl Default for GameRules {
+    fn default() -> Self {
+        GameRules { strike: 10, spare: 10 }
+    }
+}
+
+impl FromStr for GameRules {
+    type Err = Error;
+
+    /// `ten-pin`, or `no-tap` with the pins that make a strike, e.g. `no-tap-9`. Spares still take all ten pins.
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.to_ascii_lowercase();
+        match s.as_str() {
+            "ten-pin" => Ok(GameRules::default()),
+            "no-tap" => Ok(GameRules::no_tap(9)),
+            _ => match s.strip_prefix("no-tap-").map(u8::from_str) {
+                Some(Ok(strike @ 1..=10)) => Ok(GameRules::no_tap(strike)),
+                _ => bail!("Invalid game rules {}, expected ten-pin or no-tap-<pins>, e.g. no-tap-9", s),
+            },
+        }
+    }
+}
+
+impl Display for GameRules {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match *self {
+            GameRules { strike: 10, spare: 10 } => write!(f, "ten-pin"),
+            GameRules { strike, spare: 10 } => write!(f, "no-tap-{}", strike),
+            GameRules { strike, spare } => write!(f, "strike at {}, spare at {}", strike, spare),
+        }
+    }
+}
+
+impl GameRules {
+    /// Knocking down the given pins on the first ball counts as a strike
+    pub fn no_tap(strike: u8) -> GameRules {
+        GameRules { strike, ..GameRules::default() }
+    }
+
+    pub fn is_strike(&self, pins: u8) -> bool {
+        pins >= self.strike
+    }
+
+    pub fn is_spare(&self, first: u8, second: u8) -> bool {
+        !self.is_strike(first) && first + second >= self.spare
+    }
+
+    /// The pins a ball on a fresh rack counts for, a strike counting as all ten
+    pub fn fresh_rack(&self, pins: u8) -> u8 {
+        if self.is_strike(pins) { 10 } else { pins }
+    }
+
+    /// The pins the fill balls after a strike in the tenth frame count for, a strike or spare counting as all ten
+    pub fn strike_fills(&self, first_fill: u8, second_fill: u8) -> (u8, u8) {
+        let first_fill = self.fresh_rack(first_fill);
+        let second_fill = match first_fill {
+            10 => self.fresh_rack(second_fill),
+            _ if self.is_spare(first_fill, second_fill) => 10 - first_fill,
+            _ => second_fill,
+        };
+        (first_fill, second_fill)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Frame {
+    Regular(u8, u8),
+    Spare(u8),
+    Strike,
+    Tenth(TenthFrame),
+}
+
+/// The last frame of a game, where a strike or a spare earns fill balls that only count as bonus
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TenthFrame {
+    Regular(u8, u8),
+    /// First roll and fill ball
+    Spare(u8, u8),
+    /// Both fill balls
+    Strike(u8, u8),
+}
+
+impl Frame {
+    /// Number of strikes in the frame, including strikes on the fill balls of the tenth frame
+    pub fn strikes(&self) -> usize {
+        match self {
+            Frame::Strike => 1,
+            Frame::Tenth(TenthFrame::Strike(first_fill, second_fill)) => match (first_fill, second_fill) {
+                (10, 10) => 3,
+                (10, _) => 2,
+                _ => 1,
+            },
+            Frame::Tenth(TenthFrame::Spare(_, 10)) => 1,
+            _ => 0,
+        }
+    }
+
+    /// Whether the bowler left pins standing after both balls
+    pub fn is_open(&self) -> bool {
+        matches!(self, Frame::Regular(_, _) | Frame::Tenth(TenthFrame::Regular(_, _)))
+    }
+
+    /// Pins knocked down in the frame itself, not counting the fill balls of the tenth frame
+    pub fn pins(&self) -> u32 {
+        match *self {
+            Frame::Regular(first, second) | Frame::Tenth(TenthFrame::Regular(first, second)) => (first + second) as u32,
+            _ => 10,
+        }
+    }
+
+    /// Pins knocked down by each roll in the frame, including the fill balls of the tenth frame
+    pub fn rolls(&self) -> Vec<u8> {
+        match *self {
+            Frame::Regular(first, second) | Frame::Tenth(TenthFrame::Regular(first, second)) => vec![first, second],
+            Frame::Spare(first) => vec![first, 10 - first],
+            Frame::Strike => vec![10],
+            Frame::Tenth(TenthFrame::Spare(first, fill)) => vec![first, 10 - first, fill],
+            Frame::Tenth(TenthFrame::Strike(first_fill, second_fill)) => vec![10, first_fill, second_fill],
+        }
+    }
+}
+
+/// Traditional notation, e.g. `X`, `7/`, `9-` or `X7/` for the tenth frame
+impl Display for Frame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mark = |pins: u8| match pins {
+            0 => "-".to_owned(),
+            10 => "X".to_owned(),
+            _ => pins.to_string(),
+        };
+        match *self {
+            Frame::Regular(first, second) | Frame::Tenth(TenthFrame::Regular(first, second)) => write!(f, "{}{}", mark(first), mark(second)),
+            Frame::Spare(first) => write!(f, "{}/", mark(first)),
+            Frame::Strike => write!(f, "X"),
+            Frame::Tenth(TenthFrame::Spare(first, fill)) => write!(f, "{}/{}", mark(first), mark(fill)),
+            Frame::Tenth(TenthFrame::Strike(first_fill, second_fill)) if first_fill < 10 && first_fill + second_fill == 10 => {
+                write!(f, "X{}/", mark(first_fill))
+            }
+            Frame::Tenth(TenthFrame::Strike(first_fill, second_fill)) => write!(f, "X{}{}", mark(first_fill), mark(second_fill)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use super::{Frame, GameRules, TenthFrame};
+
+    #[test]
+    fn test_game_rules() {
+        for (rules, expected_rules) in [
+            ("ten-pin", GameRules { strike: 10, spare: 10 }),
+            ("no-tap", GameRules { strike: 9, spare: 10 }),
+            ("No-Tap-8", GameRules { strike: 8, spare: 10 }),
+        ] {
+            assert_eq!(GameRules::from_str(rules).unwrap(), expected_rules);
+        }
+        for rules in ["nine-pin", "no-tap-0", "no-tap-11"] {
+            assert!(GameRules::from_str(rules).is_err());
+        }
+        assert!(GameRules::no_tap(9).is_strike(9));
+        assert!(!GameRules::no_tap(9).is_spare(9, 1));
+        assert!(GameRules::no_tap(9).is_spare(8, 2));
+        assert_eq!(GameRules::no_tap(9).strike_fills(9, 9), (10, 10));
+        assert_eq!(GameRules { strike: 9, spare: 9 }.strike_fills(8, 1), (8, 2));
+        assert_eq!(GameRules::no_tap(8).to_string(), "no-tap-8");
+    }
+
+    #[test]
+    fn test_display_frame() {
+        for (frame, expected) in [
+            (Frame::Regular(9, 0), "9-"),
+            (Frame::Regular(0, 0), "--"),
+            (Frame::Spare(0), "-/"),
+            (Frame::Strike, "X"),
+            (Frame::Tenth(TenthFrame::Regular(8, 1)), "81"),
+            (Frame::Tenth(TenthFrame::Spare(7, 10)), "7/X"),
+            (Frame::Tenth(TenthFrame::Strike(10, 10)), "XXX"),
+            (Frame::Tenth(TenthFrame::Strike(0, 10)), "X-/"),
+            (Frame::Tenth(TenthFrame::Strike(10, 3)), "XX3"),
+        ] {
+            assert_eq!(frame.to_string(), expected);
+        }
+    }
+}
@@ -0,0 +1,245 @@
+use anyhow::{bail, Result};
+use crate::frame::{Frame, GameRules, TenthFrame, FRAMES_PER_GAME};
+use crate::variants::{BonusSource, FrameScore, ScoreCalculator};
+
+/// A game bowled one roll at a time, e.g. lane-side while it is in progress
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Game {
+    rules: GameRules,
+    frames: Vec<Frame>,
+    /// The rolls of the frame in progress
+    current: Vec<u8>,
+}
+
+/// A frame's score so far
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LiveFrame {
+    pub score: FrameScore,
+    /// How many rolls that the frame's bonus depends on are still to be bowled
+    pub pending_rolls: usize,
+}
+
+impl Game {
+    pub fn new() -> Game {
+        Game::default()
+    }
+
+    pub fn with_rules(rules: GameRules) -> Game {
+        Game { rules, ..Game::default() }
+    }
+
+    pub fn rules(&self) -> GameRules {
+        self.rules
+    }
+
+    /// Bowl the next roll, failing without changing the game if more pins are knocked down than are standing
+    pub fn roll(&mut self, pins: u8) -> Result<()> {
+        let Some(standing) = self.pins_standing() else {
+            bail!("The game is complete");
+        };
+        if pins > 10 {
+            bail!("Pin count over 10");
+        }
+        if pins > standing {
+            bail!("Only {} pins standing", standing);
+        }
+        self.current.push(pins);
+        if let Some(frame) = self.completed_frame() {
+            self.frames.push(frame);
+            self.current.clear();
+        }
+        Ok(())
+    }
+
+    /// The frame being bowled, counting from 1, none once the game is complete
+    pub fn frame(&self) -> Option<usize> {
+        (!self.is_complete()).then_some(self.frames.len() + 1)
+    }
+
+    /// The ball of the frame being bowled, counting from 1 and including the fill balls of the tenth frame
+    pub fn ball(&self) -> Option<usize> {
+        (!self.is_complete()).then_some(self.current.len() + 1)
+    }
+
+    /// Pins standing for the next roll, the rack being reset after a strike or spare
+    pub fn pins_standing(&self) -> Option<u8> {
+        if self.is_complete() {
+            return None;
+        }
+        Some(10 - self.rack().unwrap_or(0))
+    }
+
+    /// No ball has been bowled at the rack for the next roll yet
+    pub fn is_fresh_rack(&self) -> bool {
+        self.rack().is_none()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.frames.len() == FRAMES_PER_GAME
+    }
+
+    /// Every roll so far, a strike or spare counting as all ten pins
+    pub fn rolls(&self) -> Vec<u8> {
+        self.frames.iter().flat_map(Frame::rolls).chain(self.current.iter().copied()).collect()
+    }
+
+    /// The frames so far, the frame in progress counting as if the balls still to come were gutter balls
+    pub fn frames(&self) -> Vec<Frame> {
+        let tenth = self.frames.len() == FRAMES_PER_GAME - 1;
+        let current = match self.current[..] {
+            [] => None,
+            [first] if !tenth => Some(Frame::Regular(first, 0)),
+            [first] if self.rules.is_strike(first) => Some(Frame::Tenth(TenthFrame::Strike(0, 0))),
+            [first] => Some(Frame::Tenth(TenthFrame::Regular(first, 0))),
+            [first, first_fill] if self.rules.is_strike(first) => {
+                Some(Frame::Tenth(TenthFrame::Strike(self.rules.fresh_rack(first_fill), 0)))
+            }
+            [first, _] => Some(Frame::Tenth(TenthFrame::Spare(first, 0))),
+            _ => unreachable!("a frame of three rolls is complete"),
+        };
+        self.frames.iter().copied().chain(current).collect()
+    }
+
+    /// The score of the game so far under the variant
+    pub fn total(&self, variant: &dyn ScoreCalculator) -> u32 {
+        variant.calculate_score(&self.frames())
+    }
+
+    /// The score of every frame so far under the variant, marking the bonuses that are still to be decided. A bonus
+    /// from the next rolls is pending until those rolls are bowled, and one the variant doesn't describe is pending
+    /// until the usual strike or spare bonus rolls are.
+    pub fn score(&self, variant: &dyn ScoreCalculator) -> Vec<LiveFrame> {
+        let rolls = self.rolls().len();
+        let mut start = 0;
+        variant.score_frames(&self.frames()).into_iter()
+            .map(|score| {
+                let own_rolls = match score.frame {
+                    Frame::Strike | Frame::Tenth(TenthFrame::Strike(_, _)) => 1,
+                    _ => 2,
+                };
+                let bonus_rolls = match (score.bonus_source, score.frame) {
+                    (BonusSource::NextRolls(rolls), _) => rolls,
+                    (BonusSource::Other, Frame::Strike | Frame::Tenth(TenthFrame::Strike(_, _))) => 2,
+                    (BonusSource::Other, Frame::Spare(_) | Frame::Tenth(TenthFrame::Spare(_, _))) => 1,
+                    _ => 0,
+                };
+                let rolls_after = rolls.saturating_sub(start + own_rolls);
+                start += score.frame.rolls().len().min(own_rolls);
+                LiveFrame { score, pending_rolls: bonus_rolls.saturating_sub(rolls_after) }
+            })
+            .collect()
+    }
+
+    /// The first ball bowled at the rack for the next roll, none on a fresh rack
+    fn rack(&self) -> Option<u8> {
+        // A strike, or the second ball at a rack, leaves a fresh rack if the frame goes on
+        self.current.iter().fold(None, |rack, &pins| match rack {
+            None if !self.rules.is_strike(pins) => Some(pins),
+            _ => None,
+        })
+    }
+
+    /// The frame the rolls in progress complete, if they do
+    fn completed_frame(&self) -> Option<Frame> {
+        let rules = self.rules;
+        let tenth = self.frames.len() == FRAMES_PER_GAME - 1;
+        match (tenth, &self.current[..]) {
+            (false, &[first]) if rules.is_strike(first) => Some(Frame::Strike),
+            (false, &[first, second]) if rules.is_spare(first, second) => Some(Frame::Spare(first)),
+            (false, &[first, second]) => Some(Frame::Regular(first, second)),
+            (true, &[first, second]) if !rules.is_strike(first) && !rules.is_spare(first, second) => {
+                Some(Frame::Tenth(TenthFrame::Regular(first, second)))
+            }
+            (true, &[first, first_fill, second_fill]) if rules.is_strike(first) => {
+                let (first_fill, second_fill) = rules.strike_fills(first_fill, second_fill);
+                Some(Frame::Tenth(TenthFrame::Strike(first_fill, second_fill)))
+            }
+            (true, &[first, _, fill]) => Some(Frame::Tenth(TenthFrame::Spare(first, rules.fresh_rack(fill)))),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::frame::GameRules;
+    use crate::variants::{Variant2, Variant4, Variant5};
+    use super::Game;
+
+    fn game(rolls: &[u8]) -> Game {
+        let mut game = Game::new();
+        for pins in rolls {
+            game.roll(*pins).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn test_roll() {
+        for (rolls, expected_frame, expected_ball, expected_standing) in [
+            (vec![], Some(1), Some(1), Some(10)),
+            (vec![7], Some(1), Some(2), Some(3)),
+            (vec![7, 3, 10], Some(3), Some(1), Some(10)),
+            (vec![0; 18], Some(10), Some(1), Some(10)),
+            (vec![0; 19], Some(10), Some(2), Some(10)),
+            (vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 10, 4], Some(10), Some(3), Some(6)),
+            (vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 6, 4], Some(10), Some(3), Some(10)),
+            (vec![0; 20], None, None, None),
+            (vec![10; 12], None, None, None),
+        ] {
+            let game = game(&rolls);
+            assert_eq!((game.frame(), game.ball(), game.pins_standing()), (expected_frame, expected_ball, expected_standing), "{:?}", rolls);
+            assert_eq!(game.is_complete(), expected_frame.is_none());
+            assert_eq!(game.rolls(), rolls);
+        }
+    }
+
+    #[test]
+    fn test_illegal_rolls() {
+        for (rolls, pins, expected_error) in [
+            (vec![], 11, "Pin count over 10"),
+            (vec![7], 4, "Only 3 pins standing"),
+            (vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 10, 4], 7, "Only 6 pins standing"),
+            (vec![0; 20], 0, "The game is complete"),
+        ] {
+            let mut game = game(&rolls);
+            assert_eq!(game.roll(pins).unwrap_err().to_string(), expected_error);
+            assert_eq!(game.rolls(), rolls);
+        }
+    }
+
+    #[test]
+    fn test_score_with_pending_bonuses() {
+        // Given a game with a spare, then a strike and a ball of the next frame
+        let game = game(&[7, 3, 10, 4]);
+
+        // When it is scored with look-ahead bonuses
+        let scores = game.score(&Variant4::default());
+
+        // Then the strike still waits for one roll while the spare is decided
+        let scores = scores.iter().map(|frame| (frame.score.total, frame.pending_rolls)).collect::<Vec<_>>();
+        assert_eq!(scores, [(20, 0), (34, 1), (38, 0)]);
+        assert_eq!(game.total(&Variant4::default()), 38);
+
+        // And fixed bonuses are never pending
+        assert!(game.score(&Variant2::default()).iter().all(|frame| frame.pending_rolls == 0));
+        assert_eq!(game.score(&Variant5::default())[1].pending_rolls, 1);
+    }
+
+    #[test]
+    fn test_no_tap() {
+        // Given a no-tap game where nine pins on the first ball are a strike
+        let mut game = Game::with_rules(GameRules::no_tap(9));
+
+        // When nine pins are knocked down on every ball
+        game.roll(9).unwrap();
+        assert_eq!((game.frame(), game.ball(), game.pins_standing()), (Some(2), Some(1), Some(10)));
+        while !game.is_complete() {
+            game.roll(9).unwrap();
+        }
+
+        // Then it is a perfect game
+        assert_eq!(game.rolls(), [10; 12]);
+        assert_eq!(game.total(&Variant4::default()), 300);
+    }
+}
@@ -0,0 +1,125 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+use anyhow::{anyhow, bail, Error, Result};
+use serde::Serialize;
+use crate::scorecard::{Scorecard, Series};
+use crate::standings::{standings, standings_by};
+use crate::tiebreak::TieBreaker;
+use crate::variants::ScoreCalculator;
+
+/// Pins added to every game of a bowler, a percentage of how far their average is below the basis
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Handicap {
+    pub basis: u32,
+    pub percentage: u32,
+}
+
+impl FromStr for Handicap {
+    type Err = Error;
+
+    /// A percentage and basis like `90% of 220`
+    fn from_str(s: &str) -> Result<Self> {
+        let (percentage, basis) = s.split_once("of")
+            .ok_or_else(|| anyhow!("Invalid handicap {}, expected e.g. 90% of 220", s))?;
+        let (Ok(percentage), Ok(basis)) = (percentage.trim().trim_end_matches('%').parse(), basis.trim().parse()) else {
+            bail!("Invalid handicap {}, expected e.g. 90% of 220", s);
+        };
+        Ok(Handicap { basis, percentage })
+    }
+}
+
+impl Display for Handicap {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}% of {}", self.percentage, self.basis)
+    }
+}
+
+impl Handicap {
+    /// The handicap per game for a bowler with the average, nothing for bowlers at or above the basis
+    pub fn for_average(&self, average: u32) -> u32 {
+        self.basis.saturating_sub(average) * self.percentage / 100
+    }
+}
+
+/// A bowler's place in the handicap standings next to their place in the scratch standings
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct HandicapStanding {
+    /// Rank by total including handicap
+    pub rank: usize,
+    pub name: String,
+    pub games: usize,
+    /// Scratch pins per game, rounded down
+    pub average: u32,
+    /// Handicap per game
+    pub handicap: u32,
+    pub scratch_rank: usize,
+    pub scratch: u32,
+    /// Scratch plus the handicap for every game
+    pub total: u32,
+}
+
+/// Standings with every bowler's handicap, worked out from their average over the scorecards, added to every game
+pub fn handicap_standings(scorecards: &[Scorecard], variant: &dyn ScoreCalculator, handicap: &Handicap, tie_breakers: &[&dyn TieBreaker]) -> Vec<HandicapStanding> {
+    let scratch: HashMap<_, _> = standings(scorecards, variant, tie_breakers).into_iter()
+        .map(|standing| (standing.name.clone(), standing))
+        .collect();
+    let handicaps: HashMap<_, _> = scratch.values()
+        .map(|standing| (standing.name.as_str(), handicap.for_average(standing.total / standing.games as u32)))
+        .collect();
+    let score = |series: &Series| variant.calculate_score(&series.frames) + handicaps[series.name.as_str()];
+    standings_by(scorecards, &score, variant, tie_breakers).into_iter()
+        .map(|standing| {
+            let scratch = &scratch[&standing.name];
+            HandicapStanding {
+                rank: standing.rank,
+                games: standing.games,
+                average: scratch.total / scratch.games as u32,
+                handicap: handicaps[standing.name.as_str()],
+                scratch_rank: scratch.rank,
+                scratch: scratch.total,
+                total: standing.total,
+                name: standing.name,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use crate::frame::GameRules;
+    use crate::scorecard::{Notation, Scorecard};
+    use crate::variants::Variant4;
+    use super::{handicap_standings, Handicap};
+
+    #[test]
+    fn test_handicap() {
+        let handicap = Handicap::from_str("90% of 220").unwrap();
+        assert_eq!(handicap, Handicap { basis: 220, percentage: 90 });
+        for (average, expected_handicap) in [(150, 63), (219, 0), (220, 0), (250, 0), (0, 198)] {
+            assert_eq!(handicap.for_average(average), expected_handicap, "{}", average);
+        }
+        assert!(Handicap::from_str("90%").is_err());
+        assert!(Handicap::from_str("lots of 220").is_err());
+    }
+
+    #[test]
+    fn test_handicap_standings() {
+        // Given a strong and a weaker bowler
+        let scorecards = [Scorecard::parse("league.txt", "\
+            Perfect Game 10 10 10 10 10 10 10 10 10 10 10 10\n\
+            Bo Ling 9 0 9 0 9 0 9 0 9 0 9 0 9 0 9 0 9 0 9 0\n\
+            Bo Ling 9 1 9 0 9 0 9 0 9 0 9 0 9 0 9 0 9 0 9 0\n\
+            ", Notation::Numeric, GameRules::default()).unwrap()];
+
+        // When they are ranked with a handicap of 100% of 300
+        let standings = handicap_standings(&scorecards, &Variant4::default(), &Handicap { basis: 300, percentage: 100 }, &[]);
+
+        // Then the weaker bowler wins on handicap while still second on scratch
+        let ranks = standings.iter()
+            .map(|standing| (standing.name.as_str(), standing.rank, standing.scratch_rank, standing.average, standing.handicap, standing.scratch, standing.total))
+            .collect::<Vec<_>>();
+        assert_eq!(ranks, [("Bo Ling", 1, 2, 95, 205, 190, 600), ("Perfect Game", 2, 1, 300, 0, 300, 300)]);
+    }
+}
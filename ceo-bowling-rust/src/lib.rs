@@ -0,0 +1,304 @@
+use anyhow::{anyhow, Result};
+use itertools::Itertools;
+use crate::frame::GameRules;
+use crate::scorecard::{parse_line, Notation, Scorecard};
+use crate::variants::ScoreCalculator;
+
+pub mod compare;
+pub mod config;
+pub mod explain;
+pub mod frame;
+pub mod game;
+pub mod handicap;
+pub mod matchplay;
+pub mod names;
+pub mod outlook;
+pub mod ratings;
+pub mod registry;
+pub mod report;
+pub mod rules;
+pub mod scorecard;
+pub mod standings;
+pub mod stats;
+pub mod teams;
+pub mod tiebreak;
+pub mod tournament;
+pub mod variants;
+
+pub fn calculate_score<'a>(line: &'a str, notation: Notation, rules: GameRules, variant: &dyn ScoreCalculator) -> Result<(&'a str, u32)> {
+    let (name, series) = parse_line(line, notation, rules)
+        .map_err(|errors| anyhow!("Invalid scorecard, {}", errors.iter().join(", ")))?;
+    let score = variant.calculate_score(&series);
+    Ok((name, score))
+}
+
+/// The bowlers with the highest total in name order, several when they share it, and that total
+pub fn get_winner<'a>(scorecards: &'a [Scorecard], variant: &dyn ScoreCalculator) -> Result<(Vec<&'a str>, u32)> {
+    let totals = scorecards.iter()
+        .flat_map(|scorecard| scorecard.series.iter())
+        .map(|series| (series.name.as_str(), variant.calculate_score(&series.frames)))
+        .sorted_by_key(|p| p.0)
+        .into_grouping_map_by(|p| p.0)
+        .fold(0u32, |total, _, p| total + p.1);
+    let high_score = *totals.values().max().ok_or_else(|| anyhow!("No participants in scorecard"))?;
+    let winners = totals.into_iter()
+        .filter(|p| p.1 == high_score)
+        .map(|p| p.0)
+        .sorted()
+        .collect_vec();
+    Ok((winners, high_score))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{calculate_score, get_winner};
+    use crate::frame::GameRules;
+    use crate::scorecard::{Notation, Scorecard};
+    use crate::variants::{ScoreCalculator, Variant1, Variant2, Variant3, Variant4, Variant5};
+
+    fn scorecards(inputs: &[&str], notation: Notation) -> Vec<Scorecard> {
+        inputs.iter()
+            .map(|input| Scorecard::parse("scorecard.txt", input, notation, GameRules::default()).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_calculate_score() {
+        for (line, expected_result) in [
+            (
+                "Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3",
+                ("Yattas Del Lana", 45),
+            ),
+            ("Eve Stojbs 3 7 3 3 9 1 6 4 2 3 1 0", ("Eve Stojbs", 42)),
+        ] {
+            let variant = Variant1;
+            assert_eq!(calculate_score(line, Notation::Numeric, GameRules::default(), &variant).unwrap(), expected_result);
+        }
+    }
+
+    #[test]
+    fn test_calculate_score_variant2() {
+        for (line, expected_result) in [
+            (
+                "Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3",
+                ("Yattas Del Lana", 55),
+            ),
+            ("Eve Stojbs 3 7 3 3 9 1 6 4 2 3 1 0", ("Eve Stojbs", 57)),
+        ] {
+            let variant = Variant2::default();
+            assert_eq!(calculate_score(line, Notation::Numeric, GameRules::default(), &variant).unwrap(), expected_result);
+        }
+    }
+
+    #[test]
+    fn test_calculate_score_variant3() {
+        for (line, expected_result) in [
+            (
+                "Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3",
+                ("Yattas Del Lana", 55),
+            ),
+            ("Eve Stojbs 3 7 3 3 9 1 6 4 2 3 1 0", ("Eve Stojbs", 60)),
+        ] {
+            let variant = Variant3::default();
+            assert_eq!(calculate_score(line, Notation::Numeric, GameRules::default(), &variant).unwrap(), expected_result);
+        }
+    }
+
+    #[test]
+    fn test_calculate_score_variant4() {
+        for (line, expected_result) in [
+            (
+                "Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3",
+                ("Yattas Del Lana", 52),
+            ),
+            ("Eve Stojbs 3 7 3 3 9 1 6 4 2 3 1 0", ("Eve Stojbs", 53)),
+        ] {
+            let variant = Variant4::default();
+            assert_eq!(calculate_score(line, Notation::Numeric, GameRules::default(), &variant).unwrap(), expected_result);
+        }
+    }
+
+    #[test]
+    fn test_calculate_score_variant4_tenth_frame() {
+        for (line, expected_result) in [
+            (
+                "Perfect Game 10 10 10 10 10 10 10 10 10 10 10 10",
+                ("Perfect Game", 300),
+            ),
+            (
+                "Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3 0 0 0 0 0 0 5 5 7",
+                ("Yattas Del Lana", 69),
+            ),
+            (
+                "Eve Stojbs 3 7 3 3 9 1 6 4 2 3 1 0 0 0 0 0 0 0 10 7 3",
+                ("Eve Stojbs", 73),
+            ),
+        ] {
+            let variant = Variant4::default();
+            assert_eq!(calculate_score(line, Notation::Numeric, GameRules::default(), &variant).unwrap(), expected_result);
+        }
+    }
+
+    #[test]
+    fn test_calculate_score_fill_balls_are_not_frames() {
+        // Given a perfect game
+        let line = "Perfect Game 10 10 10 10 10 10 10 10 10 10 10 10";
+
+        // When it is scored without look-ahead bonuses
+        let variant = Variant1;
+
+        // Then the fill balls are not counted as extra frames
+        assert_eq!(calculate_score(line, Notation::Numeric, GameRules::default(), &variant).unwrap(), ("Perfect Game", 100));
+    }
+
+    #[test]
+    fn test_calculate_score_no_tap() {
+        // Given no-tap rules where nine pins on the first ball are a strike
+        let rules = GameRules::no_tap(9);
+
+        // When games are scored with and without look-ahead bonuses
+        for (line, variant, expected_result) in [
+            ("Perfect Game 9 9 9 9 9 9 9 9 9 9 9 9", &Variant4::default() as &dyn ScoreCalculator, ("Perfect Game", 300)),
+            ("Perfect Game 9 9 9 9 9 9 9 9 9 9 9 9", &Variant1, ("Perfect Game", 100)),
+            ("Yattas Del Lana 9 5 4 3 0", &Variant4::default(), ("Yattas Del Lana", 31)),
+        ] {
+            // Then the strikes count as all ten pins
+            assert_eq!(calculate_score(line, Notation::Numeric, rules, variant).unwrap(), expected_result, "{}", line);
+        }
+        assert!(calculate_score("Yattas Del Lana 9 5 4 3 0", Notation::Numeric, GameRules::default(), &Variant4::default()).is_err());
+    }
+
+    #[test]
+    fn test_calculate_score_too_many_frames() {
+        for line in [
+            "Eve Stojbs 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1",
+            "Eve Stojbs 10 10 10 10 10 10 10 10 10 10 10 10 10",
+            "Eve Stojbs 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 5 5",
+        ] {
+            assert!(calculate_score(line, Notation::Numeric, GameRules::default(), &Variant1).is_err());
+        }
+    }
+
+    #[test]
+    fn test_calculate_score_variant5() {
+        for (line, expected_result) in [
+            (
+                "Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3",
+                ("Yattas Del Lana", 207),
+            ),
+            ("Eve Stojbs 3 7 3 3 9 1 6 4 2 3 1 0", ("Eve Stojbs", 212)),
+        ] {
+            let variant = Variant5::default();
+            assert_eq!(calculate_score(line, Notation::Numeric, GameRules::default(), &variant).unwrap(), expected_result);
+        }
+    }
+
+    #[test]
+    fn test_get_winner() {
+        // Given a scorecard and an aexpected winner
+        for (input, expected_winner) in [
+            ("Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3\nEve Stojbs 3 7 3 3 9 1 6 4 2 3 1 0\n", (vec!["Yattas Del Lana"], 45)),
+            ("Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3\nEve Stojbs 3 7 3 3 9 1 6 4 2 3 1 5\n", (vec!["Eve Stojbs"], 47)),
+        ] {
+            // And scoring variant 1
+            let variant = Variant1;
+
+            // Expect the winner to be as expected
+            assert_eq!(get_winner(&scorecards(&[input], Notation::Numeric), &variant).unwrap(), expected_winner)
+        }
+    }
+
+    #[test]
+    fn test_get_winner_variant2() {
+        for input in [
+            "\
+            Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3\n\
+            Eve Stojbs 3 7 3 3 9 1 6 4 2 3 1 0\n\
+            ",
+            "\
+            Eve Stojbs 3 7 3 3 9 1 6 4 2 3 1 0\n\
+            Yattas Del Lana 1 5 3 2 7 3 3 0 10 4 3\n\
+            ",
+        ] {
+            let variant = Variant2::default();
+            assert_eq!(get_winner(&scorecards(&[input], Notation::Numeric), &variant).unwrap(), (vec!["Eve Stojbs"], 57))
+        }
+    }
+
+    #[test]
+    fn test_get_winner_multiple_scorecards() {
+        let input = [
+            "\
+            Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3\n\
+            Eve Stojbs 3 7 3 3 9 1 6 4 2 3 1 0\n\
+            ",
+            "\
+            Eve Stojbs 1 1\n\
+            Yattas Del Lana 1 1\n\
+            ",
+        ];
+        let variant = Variant2::default();
+        assert_eq!(get_winner(&scorecards(&input, Notation::Numeric), &variant).unwrap(), (vec!["Eve Stojbs"], 59))
+    }
+
+    #[test]
+    fn test_calculate_score_invalid_frames() {
+        for (line, expected_error) in [
+            ("Eve Stojbs 9 9", "column 14: Frame total of 18 is over 10 '9'"),
+            ("Eve Stojbs 3 11", "column 14: Pin count over 10 '11'"),
+            ("Eve Stojbs 3 7 x", "column 16: Not a pin count 'x'"),
+            ("Eve Stojbs 3 7 3", "column 16: Missing second roll of frame '3'"),
+        ] {
+            let error = calculate_score(line, Notation::Numeric, GameRules::default(), &Variant1).unwrap_err();
+            assert_eq!(error.to_string(), format!("Invalid scorecard, {}", expected_error));
+        }
+    }
+
+    #[test]
+    fn test_calculate_score_traditional_notation() {
+        for (line, numeric_line, expected_result) in [
+            (
+                "Eve Stojbs X 7/ 9- F8 X X 81 S8/ -- XXX",
+                "Eve Stojbs 10 7 3 9 0 0 8 10 10 8 1 8 2 0 0 10 10 10",
+                ("Eve Stojbs", 152),
+            ),
+            (
+                "Yattas Del Lana 35 35 72 3- X 43 -- -- -- 5/7",
+                "Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3 0 0 0 0 0 0 5 5 7",
+                ("Yattas Del Lana", 69),
+            ),
+        ] {
+            // Given scoring variant 4
+            let variant = Variant4::default();
+
+            // Expect the traditional notation to score the same as the numeric one
+            assert_eq!(calculate_score(line, Notation::Traditional, GameRules::default(), &variant).unwrap(), expected_result);
+            assert_eq!(calculate_score(numeric_line, Notation::Numeric, GameRules::default(), &variant).unwrap(), expected_result);
+        }
+    }
+
+    #[test]
+    fn test_calculate_score_invalid_traditional_notation() {
+        for (line, expected_error) in [
+            ("Eve Stojbs X/ 7/", "column 13: Spare without a first roll '/'"),
+            ("Eve Stojbs 7/ 9?", "column 16: Not a roll '?'"),
+            ("Eve Stojbs 7/ 99", "column 16: Frame total of 18 is over 10 '9'"),
+        ] {
+            let error = calculate_score(line, Notation::Traditional, GameRules::default(), &Variant1).unwrap_err();
+            assert_eq!(error.to_string(), format!("Invalid scorecard, {}", expected_error));
+        }
+    }
+
+    #[test]
+    fn test_get_winner_traditional_notation() {
+        let input = "Yattas Del Lana 35 35 72 3- X 43\nEve Stojbs 37 33 91 64 23 1-\n";
+        assert_eq!(get_winner(&scorecards(&[input], Notation::Traditional), &Variant2::default()).unwrap(), (vec!["Eve Stojbs"], 57))
+    }
+
+    #[test]
+    fn test_get_winner_tie() {
+        let input = "Yattas Del Lana 4 5\nEve Stojbs 9 0\nBo Ling 4 4\n";
+        let scorecards = scorecards(&[input], Notation::Numeric);
+        assert_eq!(get_winner(&scorecards, &Variant1).unwrap(), (vec!["Eve Stojbs", "Yattas Del Lana"], 9));
+    }
+}
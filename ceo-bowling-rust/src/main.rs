@@ -1,342 +1,509 @@
-use anyhow::{anyhow, bail, Error, Result};
-use itertools::{Itertools, process_results};
+use anyhow::{anyhow, bail, Context, Result};
+use itertools::Itertools;
+use jfokus23_rust::compare::{compare, Comparison};
+use jfokus23_rust::explain::explain;
+use jfokus23_rust::report::{Format, FramesReport, HandicapReport, StandingsReport};
+use jfokus23_rust::scorecard::{parse_rolls, InputFormat, Notation, Scorecard};
+use jfokus23_rust::teams::{team_standings, Roster, TeamStanding};
+use jfokus23_rust::stats::{stats, Stats};
+use jfokus23_rust::standings::{standings, Standing};
+use jfokus23_rust::tiebreak::{FewestOpenFrames, HighGame, MostStrikes, RollOff, TieBreak, TieBreaker};
+use jfokus23_rust::tournament::{Finals, Tournament};
+use jfokus23_rust::handicap::{handicap_standings, Handicap, HandicapStanding};
+use jfokus23_rust::matchplay::{play, points_standings, MatchResult, PointsStanding, Schedule};
+use jfokus23_rust::frame::GameRules;
+use jfokus23_rust::game::Game;
+use jfokus23_rust::names::{Names, Normalization};
+use jfokus23_rust::outlook::{needed, outlook};
+use jfokus23_rust::ratings::{Event, RatingSystem, Ratings};
+use jfokus23_rust::registry::Registry;
+use jfokus23_rust::variants::{ScoreCalculator, Variant1};
 use std::env;
 use std::fs::File;
-use std::io::Read;
+use std::io::{self, BufReader};
+use std::rc::Rc;
 use std::str::FromStr;
 
 fn main() -> Result<()> {
-    let mut args = env::args().skip(1);
-    let variant: Box<dyn ScoreCalculator> = match args.next() {
-        Some(variant) if variant.eq_ignore_ascii_case("variant1") || variant.eq_ignore_ascii_case("1") => Box::new(Variant1::default()),
-        Some(variant) if variant.eq_ignore_ascii_case("variant2") || variant.eq_ignore_ascii_case("2") => Box::new(Variant2::default()),
-        Some(variant) if variant.eq_ignore_ascii_case("variant3") || variant.eq_ignore_ascii_case("3") => Box::new(Variant3::default()),
-        Some(variant) if variant.eq_ignore_ascii_case("variant4") || variant.eq_ignore_ascii_case("4") => Box::new(Variant4::default()),
-        Some(variant) if variant.eq_ignore_ascii_case("variant5") || variant.eq_ignore_ascii_case("5") => Box::new(Variant5::default()),
-        Some(variant) => bail!("Invalid scoring variant {}", variant),
-        None => Box::new(Variant1::default()),
+    let mut args = env::args().skip(1).collect_vec();
+    let notation = take_option(&mut args, "--notation")?
+        .map(|notation| Notation::from_str(&notation))
+        .transpose()?
+        .unwrap_or_default();
+    let game_rules = take_option(&mut args, "--game-rules")?
+        .map(|game_rules| GameRules::from_str(&game_rules))
+        .transpose()?
+        .unwrap_or_default();
+    let input_format = take_option(&mut args, "--input-format")?
+        .map(|input_format| InputFormat::from_str(&input_format))
+        .transpose()?;
+    let format = take_option(&mut args, "--format")?
+        .map(|format| Format::from_str(&format))
+        .transpose()?
+        .unwrap_or_default();
+    let mut names = Names::new(take_option(&mut args, "--normalize")?
+        .map(|normalization| Normalization::from_str(&normalization))
+        .transpose()?
+        .unwrap_or_default());
+    while let Some(alias_file) = take_option(&mut args, "--aliases")? {
+        names.load_aliases(alias_file)?;
+    }
+    let handicap = take_option(&mut args, "--handicap")?
+        .map(|handicap| Handicap::from_str(&handicap))
+        .transpose()?;
+    let roster = take_option(&mut args, "--roster")?
+        .map(Roster::load)
+        .transpose()?;
+    let schedule = take_option(&mut args, "--schedule")?
+        .map(Schedule::load)
+        .transpose()?;
+    let finals = take_option(&mut args, "--finals")?
+        .map(|finals| Finals::from_str(&finals))
+        .transpose()?
+        .unwrap_or(Finals::Stepladder);
+    let seeds = take_option(&mut args, "--seeds")?
+        .map(|seeds| seeds.parse::<usize>().with_context(|| format!("Invalid number of seeds {}", seeds)))
+        .transpose()?;
+    let target = take_option(&mut args, "--target")?
+        .map(|target| target.parse::<u32>().with_context(|| format!("Invalid target score {}", target)))
+        .transpose()?;
+    let rolls = take_option(&mut args, "--rolls")?;
+    let ratings_file = take_option(&mut args, "--ratings")?;
+    let rating_system = take_option(&mut args, "--rating-system")?
+        .map(|rating_system| RatingSystem::from_str(&rating_system))
+        .transpose()?;
+    let mut match_files = Vec::new();
+    while let Some(match_file) = take_option(&mut args, "--match")? {
+        match_files.push(match_file);
+    }
+    let tie_breaks = take_option(&mut args, "--tie-break")?
+        .map(|rules| rules.split(',').map(TieBreak::from_str).collect::<Result<Vec<_>>>())
+        .transpose()?
+        .unwrap_or_default();
+    let roll_off = take_option(&mut args, "--roll-off")?
+        .map(|roll_off_file| Ok::<_, anyhow::Error>(RollOff { scorecards: read_scorecards(&[roll_off_file], input_format, notation, game_rules, &mut names)? }))
+        .transpose()?;
+    let tie_breakers = tie_breaks.iter()
+        .map(|tie_break| Ok(match tie_break {
+            TieBreak::HighGame => &HighGame as &dyn TieBreaker,
+            TieBreak::MostStrikes => &MostStrikes,
+            TieBreak::FewestOpenFrames => &FewestOpenFrames,
+            TieBreak::RollOff => roll_off.as_ref().ok_or_else(|| anyhow!("The roll-off tie-break needs --roll-off"))?,
+        }))
+        .collect::<Result<Vec<_>>>()?;
+    let mut registry = Registry::default();
+    let mut rule_file_variant = None;
+    while let Some(rule_file) = take_option(&mut args, "--rules")? {
+        let entry = registry.load(rule_file)?;
+        rule_file_variant = Some((entry.name.clone(), entry.variant.clone()));
+    }
+    let command = match args.first().map(String::as_str) {
+        Some("list-variants" | "frames" | "explain" | "compare" | "stats" | "teams" | "match-play" | "tournament" | "ratings" | "outlook") => args.remove(0),
+        _ => "standings".to_owned(),
+    };
+    if format != Format::Text && !matches!(command.as_str(), "standings" | "frames") {
+        bail!("Only standings and frames can be written as JSON or CSV");
+    }
+    if command == "list-variants" {
+        print_variants(&registry);
+        return Ok(());
+    }
+    if command == "compare" {
+        // Every variant is used, so all arguments are input files
+        let scorecards = read_scorecards(&args, input_format, notation, game_rules, &mut names)?;
+        print_comparison(&compare(&scorecards, &registry, &tie_breakers));
+        return Ok(());
+    }
+    let mut args = args.into_iter().peekable();
+    // The variant can be left out when a rule file is given, in which case the last rule file is used
+    let (variant_name, variant): (String, Rc<dyn ScoreCalculator>) = match args.peek().and_then(|name| registry.get(name)) {
+        Some(entry) => {
+            args.next();
+            (entry.name.clone(), entry.variant.clone())
+        }
+        None => match (rule_file_variant, args.peek()) {
+            (Some(entry), _) => entry,
+            (None, Some(name)) if name != "-" => bail!("Invalid scoring variant {}", name),
+            (None, _) => ("variant1".to_owned(), Rc::new(Variant1)),
+        },
     };
+    if command == "outlook" {
+        // The rolls of the game in progress are an option, so that the first of them is never taken for the variant
+        if let Some(arg) = args.next() {
+            bail!("Unexpected argument {}, the rolls of the game go in --rolls", arg);
+        }
+        let rolls = parse_rolls(&rolls.unwrap_or_default(), notation, game_rules)
+            .map_err(|errors| anyhow!("Invalid rolls, {}", errors.iter().join(", ")))?;
+        let mut game = Game::with_rules(game_rules);
+        for (index, pins) in rolls.into_iter().enumerate() {
+            game.roll(pins).with_context(|| format!("Invalid roll {}", index + 1))?;
+        }
+        print_outlook(&game, target, &variant_name, variant.as_ref())?;
+        return Ok(());
+    }
     let input_files = args.collect_vec();
-    if input_files.is_empty() {
-        bail!("No input");
-    }
-    let scorecards = input_files.iter().map(|input_file| {
-        let mut input = String::new();
-        File::open(input_file).and_then(|mut f| f.read_to_string(&mut input))?;
-        Ok(input)
-    }).collect::<Result<Vec<_>, Error>>()?;
-    let winner = get_winner(&scorecards, variant.as_ref())?;
-    println!("The winner is {} with a score of {}", winner.0, winner.1);
+    let scorecards = read_scorecards(&input_files, input_format, notation, game_rules, &mut names)?;
+    if command == "frames" {
+        let report = FramesReport::new(&variant_name, &scorecards, variant.as_ref());
+        return match format {
+            Format::Text => {
+                print_frames(&report);
+                Ok(())
+            }
+            Format::Json => report.write_json(io::stdout()),
+            Format::Csv => report.write_csv(io::stdout()),
+        };
+    }
+    if command == "explain" {
+        print_explanations(&scorecards, &variant_name, variant.as_ref());
+        return Ok(());
+    }
+    let roster = roster.map(|mut roster| {
+        for bowler in roster.teams.iter_mut().flat_map(|team| team.bowlers.iter_mut().chain(&mut team.substitutes)) {
+            *bowler = names.resolve(bowler);
+        }
+        roster
+    });
+    if command == "match-play" {
+        let Some(mut schedule) = schedule else {
+            bail!("Match play needs a --schedule file");
+        };
+        for game_match in &mut schedule.matches {
+            for side in [&mut game_match.home, &mut game_match.away] {
+                if !roster.as_ref().is_some_and(|roster| roster.teams.iter().any(|team| team.name == *side)) {
+                    *side = names.resolve(side);
+                }
+            }
+        }
+        let results = play(&schedule, &scorecards, roster.as_ref(), variant.as_ref())?;
+        print_match_results(&results);
+        println!();
+        print_points_standings(&points_standings(&results));
+        return Ok(());
+    }
+    if command == "teams" {
+        let Some(roster) = roster else {
+            bail!("Team standings need a --roster file");
+        };
+        for name in scorecards.iter().flat_map(|scorecard| &scorecard.series).map(|series| &series.name).unique() {
+            if roster.team_of(name).is_none() {
+                eprintln!("{} is not on any team", name);
+            }
+        }
+        let standings = team_standings(&scorecards, &roster, variant.as_ref());
+        print_team_standings(&standings, &scorecards);
+        println!();
+        let winners = standings.iter().take_while(|standing| standing.rank == 1).map(|standing| standing.name.clone()).collect_vec();
+        match standings.first() {
+            Some(winner) => print_winners(&winners, winner.total),
+            None => bail!("No teams in scorecard"),
+        }
+        return Ok(());
+    }
+    if command == "tournament" {
+        // The qualifying standings seed the finals, every --match scorecard being the next match in order
+        let qualifying = standings(&scorecards, variant.as_ref(), &tie_breakers);
+        let mut tournament = Tournament::from_standings(finals, &qualifying, seeds.unwrap_or(qualifying.len()))?;
+        println!("Seeds: {}", tournament.seeds.iter().enumerate().map(|(index, seed)| format!("{}. {}", index + 1, seed)).join(", "));
+        for match_file in &match_files {
+            let round = tournament.round();
+            let scorecard = read_scorecards(std::slice::from_ref(match_file), input_format, notation, game_rules, &mut names)?.remove(0);
+            match tournament.record(&scorecard, variant.as_ref())? {
+                Some(outcome) => println!("Round {}: {} {} - {} {}, {} advances", round,
+                                          outcome.scores[0].0, outcome.scores[0].1, outcome.scores[1].1, outcome.scores[1].0, outcome.winner),
+                None => println!("Round {}: a tie on {}, the next match scorecard is a roll-off", round, scorecard.name),
+            }
+        }
+        match (tournament.champion(), tournament.next_match()) {
+            (Some(champion), _) => println!("The champion is {}", champion),
+            (None, Some((first, second))) => println!("Next up in round {}: {} against {}", tournament.round(), first, second),
+            (None, None) => unreachable!(),
+        }
+        return Ok(());
+    }
+    if command == "ratings" {
+        // Every scorecard is an event, rated in the order given
+        let Some(ratings_file) = ratings_file else {
+            bail!("Ratings need a --ratings file to keep them in");
+        };
+        let mut ratings = Ratings::load(&ratings_file, rating_system)?;
+        let mut events = Vec::new();
+        for scorecard in &scorecards {
+            match ratings.rate(scorecard, variant.as_ref()) {
+                Some(event) => events.push(event),
+                None => eprintln!("{} has already been rated", scorecard.name),
+            }
+        }
+        ratings.save(&ratings_file)?;
+        print_ratings(&ratings, &events);
+        return Ok(());
+    }
+    if command == "stats" {
+        print_stats(&stats(&scorecards, variant.as_ref()));
+        return Ok(());
+    }
+    if let Some(handicap) = handicap {
+        let report = HandicapReport::new(&variant_name, handicap, handicap_standings(&scorecards, variant.as_ref(), &handicap, &tie_breakers));
+        let Some(winning_score) = report.winning_score else {
+            bail!("No participants in scorecard");
+        };
+        match format {
+            Format::Json => return report.write_json(io::stdout()),
+            Format::Csv => return report.write_csv(io::stdout()),
+            Format::Text => {}
+        }
+        print_handicap_standings(&report.standings, &handicap);
+        println!();
+        print_winners(&report.winners, winning_score);
+        return Ok(());
+    }
+    let report = StandingsReport::new(&variant_name, standings(&scorecards, variant.as_ref(), &tie_breakers));
+    let Some(winning_score) = report.winning_score else {
+        bail!("No participants in scorecard");
+    };
+    match format {
+        Format::Json => return report.write_json(io::stdout()),
+        Format::Csv => return report.write_csv(io::stdout()),
+        Format::Text => {}
+    }
+    print_standings(&report.standings, &scorecards);
+    println!();
+    print_winners(&report.winners, winning_score);
     Ok(())
 }
 
-enum Frame {
-    Regular(u8, u8),
-    Spare(u8),
-    Strike,
-}
-
-trait ScoreCalculator {
-    fn calculate_score(&self, series: &[Frame]) -> u32;
-}
-
-#[derive(Default)]
-struct Variant1;
-
-impl ScoreCalculator for Variant1 {
-    fn calculate_score(&self, series: &[Frame]) -> u32 {
-        series.iter()
-            .map(|roll| match roll {
-                Frame::Regular(first, second) => (first + second) as u32,
-                Frame::Spare(_) | Frame::Strike => 10u32,
-            })
-            .sum()
+fn print_winners(winners: &[String], winning_score: u32) {
+    if winners.len() == 1 {
+        println!("The winner is {} with a score of {}", winners[0], winning_score);
+    } else {
+        println!("It's a tie between {} with a score of {}", winners.join(" and "), winning_score);
     }
 }
 
-struct Variant2 {
-    spare_bonus: u32,
-    strike_bonus: u32,
+/// Print a scoresheet row with the running total under each frame for every series
+fn print_frames(report: &FramesReport) {
+    for series in &report.series {
+        println!("{} ({})", series.name, series.scorecard);
+        println!("{:<5}{}", "Frame", series.frames.iter().map(|frame| format!("{:>5}", frame.frame)).join(""));
+        println!("{:<5}{}", "Rolls", series.frames.iter().map(|frame| format!("{:>5}", frame.marks)).join(""));
+        println!("{:<5}{}", "Score", series.frames.iter().map(|frame| format!("{:>5}", frame.total)).join(""));
+        println!();
+    }
 }
 
-impl Default for Variant2 {
-    fn default() -> Self {
-        Variant2 {
-            spare_bonus: 5,
-            strike_bonus: 10,
+/// Print how every series was scored, broken down by the variants the scoring variant adds up
+fn print_explanations(scorecards: &[Scorecard], variant_name: &str, variant: &dyn ScoreCalculator) {
+    for scorecard in scorecards {
+        for series in &scorecard.series {
+            println!("{} ({})", series.name, scorecard.name);
+            println!("{}", explain(variant_name, &series.frames, variant));
         }
     }
 }
 
-impl ScoreCalculator for Variant2 {
-    fn calculate_score(&self, series: &[Frame]) -> u32 {
-        series.iter()
-            .map(|roll| match roll {
-                Frame::Regular(first, second) => (first + second) as u32,
-                Frame::Spare(_) => 10 + self.spare_bonus,
-                Frame::Strike => 10 + self.strike_bonus,
+/// Print the total and rank of every bowler under every variant, marking the winners with a `*`
+fn print_comparison(comparison: &Comparison) {
+    let name_width = comparison.bowlers.iter().map(|bowler| bowler.name.chars().count()).max().unwrap_or(0).max(4);
+    let widths = comparison.variants.iter().map(|variant| variant.chars().count().max(10)).collect_vec();
+    println!("{:<name_width$}  {}", "Name", comparison.variants.iter()
+        .zip(&widths)
+        .map(|(variant, width)| format!("{:>width$}", variant, width = width))
+        .join("  "));
+    for bowler in &comparison.bowlers {
+        println!("{:<name_width$}  {}", bowler.name, bowler.results.iter()
+            .zip(&widths)
+            .map(|(result, width)| {
+                let winner = if result.rank == 1 { "*" } else { " " };
+                format!("{:>width$}", format!("{} ({}){}", result.total, result.rank, winner), width = width)
             })
-            .sum()
+            .join("  "));
     }
-}
-
-struct Variant3 {
-    spare_bonus: u32,
-    spare_increment: u32,
-    strike_bonus: u32,
-    strike_increment: u32,
-}
-
-impl Default for Variant3 {
-    fn default() -> Self {
-        Variant3 {
-            spare_bonus: 5,
-            spare_increment: 1,
-            strike_bonus: 10,
-            strike_increment: 2,
-        }
+    println!();
+    let winner_changes = comparison.winner_changes();
+    for change in &winner_changes {
+        println!("The winner changes from {} under {} to {} under {}",
+                 change.from_winners.join(" and "), change.from_variant,
+                 change.to_winners.join(" and "), change.to_variant);
+    }
+    if winner_changes.is_empty() {
+        println!("The winner is the same under every variant");
     }
 }
 
-impl ScoreCalculator for Variant3 {
-    fn calculate_score(&self, series: &[Frame]) -> u32 {
-        series.iter()
-            .fold((0u32, self.spare_bonus, self.strike_bonus), |state, frame| {
-                let (score, spare_bonus, strike_bonus) = state;
-                let score = score + match frame {
-                    Frame::Regular(first, second) => (first + second) as u32,
-                    Frame::Spare(_) => 10 + spare_bonus,
-                    Frame::Strike => 10 + strike_bonus,
-                };
-                let (spare_bonus, strike_bonus) = match frame {
-                    Frame::Regular(_, _) => (spare_bonus, strike_bonus),
-                    Frame::Spare(_) => (spare_bonus + self.spare_increment, strike_bonus),
-                    Frame::Strike => (spare_bonus, strike_bonus + self.strike_increment),
-                };
-                (score, spare_bonus, strike_bonus)
-            }).0
+fn print_variants(registry: &Registry) {
+    let name_width = registry.entries().iter().map(|entry| entry.name.chars().count()).max().unwrap_or(0);
+    for entry in registry.entries() {
+        let aliases = match entry.aliases.is_empty() {
+            true => String::new(),
+            false => format!(" ({})", entry.aliases.join(", ")),
+        };
+        println!("{:<width$}  {}", format!("{}{}", entry.name, aliases), entry.description, width = name_width + 5);
     }
 }
 
-struct Variant4 {}
-
-impl Default for Variant4 {
-    fn default() -> Self {
-        Variant4 {
+/// Read and parse scorecard files in the given format or the one their extension suggests, printing every problem
+/// found in any of them before failing. Standard input is read when there are no files or a file is `-`. Bowler
+/// names are resolved so that the same bowler has the same name on every scorecard.
+fn read_scorecards(input_files: &[String], input_format: Option<InputFormat>, notation: Notation, game_rules: GameRules, names: &mut Names) -> Result<Vec<Scorecard>> {
+    let stdin = ["-".to_owned()];
+    let input_files = if input_files.is_empty() { &stdin[..] } else { input_files };
+    let (mut scorecards, errors): (Vec<_>, Vec<_>) = input_files.iter().map(|input_file| {
+        if input_file == "-" {
+            let input_format = input_format.unwrap_or_default();
+            return Scorecard::read_from("<stdin>", io::stdin().lock(), input_format, notation, game_rules)
+                .context("Could not read standard input");
+        }
+        let input_format = input_format.unwrap_or_else(|| InputFormat::from_path(input_file));
+        File::open(input_file)
+            .and_then(|file| Scorecard::read_from(input_file, BufReader::new(file), input_format, notation, game_rules))
+            .with_context(|| format!("Could not read {}", input_file))
+    }).collect::<Result<Vec<_>>>()?
+        .into_iter()
+        .partition_result();
+    let diagnostics = errors.into_iter().flat_map(|error| error.0).collect_vec();
+    if !diagnostics.is_empty() {
+        for diagnostic in &diagnostics {
+            eprintln!("{}", diagnostic);
         }
+        bail!("Invalid scorecard, found {} errors", diagnostics.len());
     }
+    names.apply(&mut scorecards);
+    Ok(scorecards)
 }
 
-impl ScoreCalculator for Variant4 {
-    fn calculate_score(&self, series: &[Frame]) -> u32 {
-        series.iter()
-            .rev()
-            .fold((0u32, 0u8, 0u8), |state, frame| {
-                let (score, next_roll, second_next_roll) = state;
-                let score = score + match frame {
-                    Frame::Regular(first, second) => (first + second) as u32,
-                    Frame::Spare(_) => (10 + next_roll) as u32,
-                    Frame::Strike => (10 + next_roll + second_next_roll) as u32,
-                };
-                let (next_roll, second_next_roll) = match frame {
-                    Frame:: Regular(first, second) => (*first, *second),
-                    Frame::Spare(first) => (*first, 10 - first),
-                    Frame::Strike => (10, next_roll),
-                };
-                (score, next_roll, second_next_roll)
-            }).0
+fn print_standings(standings: &[Standing], scorecards: &[Scorecard]) {
+    let name_width = standings.iter().map(|standing| standing.name.chars().count()).max().unwrap_or(0).max(4);
+    let scorecard_names = scorecards.iter().map(|scorecard| scorecard.name.as_str()).unique().collect_vec();
+    println!("{:>4}  {:<name_width$}  {:>5}  {:>5}  {}", "Rank", "Name", "Total", "Games",
+             scorecard_names.iter().map(|name| format!("{:>5}", name)).join("  "));
+    for standing in standings {
+        let subtotals = scorecard_names.iter()
+            .map(|name| {
+                let subtotal = standing.subtotals.iter()
+                    .find(|subtotal| subtotal.scorecard == *name)
+                    .map_or("-".to_owned(), |subtotal| subtotal.total.to_string());
+                format!("{:>width$}", subtotal, width = name.chars().count().max(5))
+            })
+            .join("  ");
+        println!("{:>4}  {:<name_width$}  {:>5}  {:>5}  {}", standing.rank, standing.name, standing.total, standing.games, subtotals);
     }
 }
-struct Variant5 {
-    variant1: Variant1,
-    variant2: Variant2,
-    variant3: Variant3,
-    variant4: Variant4,
-}
 
-impl Default for Variant5 {
-    fn default() -> Self {
-        Variant5 {
-            variant1: Variant1::default(),
-            variant2: Variant2::default(),
-            variant3: Variant3::default(),
-            variant4: Variant4::default(),
-        }
+/// Print the handicap standings with the scratch rank and total of every bowler next to them
+fn print_handicap_standings(standings: &[HandicapStanding], handicap: &Handicap) {
+    let name_width = standings.iter().map(|standing| standing.name.chars().count()).max().unwrap_or(0).max(4);
+    println!("Handicap {}", handicap);
+    println!("{:>4}  {:<name_width$}  {:>5}  {:>7}  {:>3}  {:>7}  {:>5}", "Rank", "Name", "Games", "Average", "Hcp", "Scratch", "Total");
+    for standing in standings {
+        println!("{:>4}  {:<name_width$}  {:>5}  {:>7}  {:>3}  {:>7}  {:>5}",
+                 standing.rank, standing.name, standing.games, standing.average, standing.handicap,
+                 format!("{} ({})", standing.scratch, standing.scratch_rank), standing.total);
     }
 }
 
-impl ScoreCalculator for Variant5 {
-    fn calculate_score(&self, series: &[Frame]) -> u32 {
-        let variants: &[&dyn ScoreCalculator] = &[&self.variant1, &self.variant2, &self.variant3, &self.variant4];
-            variants.iter()
-            .map(|variant| variant.calculate_score(series))
-            .sum()
+fn print_match_results(results: &[MatchResult]) {
+    for result in results {
+        let games = result.games.iter()
+            .map(|(home, away)| {
+                let score = |score: &Option<u32>| score.map_or("-".to_owned(), |score| score.to_string());
+                format!("{}-{}", score(home), score(away))
+            })
+            .join(", ");
+        println!("{}: {} {} - {} {} (games {}, total {}-{})",
+                 result.scorecard, result.home, result.home_points, result.away_points, result.away,
+                 games, result.home_total, result.away_total);
     }
 }
 
-fn calculate_score<'a>(line: &'a str, variant: &dyn ScoreCalculator) -> Result<(&'a str, u32)> {
-    let Some(score_start) = line.find(char::is_numeric) else {
-        return Ok((line.trim(), 0));
-    };
-    let (name, scores) = line.split_at(score_start);
-    let name = name.trim();
-    let score = process_results(scores.split(" ").map(u8::from_str), |mut scores| -> Result<u32, Error> {
-        let mut series = Vec::new();
-        loop {
-            let Some(first_roll) = scores.next() else {
-                break;
-            };
-            let roll = if first_roll == 10 {
-                Frame::Strike
-            } else {
-                let second_roll = scores.next().ok_or_else(|| anyhow!("Invalid scorecard"))?;
-                if first_roll + second_roll == 10 {
-                    Frame::Spare(first_roll)
-                } else {
-                    Frame::Regular(first_roll, second_roll)
-                }
-            };
-            series.push(roll);
-        }
-        Ok(variant.calculate_score(&series))
-    })??;
-    Ok(dbg!((name, score)))
-}
-
-fn get_winner<'a>(scorecards: &'a[impl AsRef<str>], variant: &dyn ScoreCalculator) -> Result<(&'a str, u32)> {
-    process_results(scorecards.iter()
-                        .flat_map(|scorecard|
-                            scorecard.as_ref()
-                                .split("\n")
-                                .map(|series| calculate_score(series, variant))),
-                    |scores| scores
-                        .sorted_by_key(|p| p.0)
-                        .into_grouping_map_by(|p| p.0)
-                        .fold(0u32, |total, _, p| total + p.1)
-                        .into_iter()
-                        .max_by_key(|p| p.1)
-                        .ok_or_else(|| anyhow!("No participants in scorecard")))?
-}
-
-#[cfg(test)]
-mod tests {
-    use crate::{calculate_score, get_winner, Variant1, Variant2, Variant3, Variant4, Variant5};
-
-    #[test]
-    fn test_calculate_score() {
-        for (line, expected_result) in [
-            (
-                "Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3",
-                ("Yattas Del Lana", 45),
-            ),
-            ("Eve Stojbs 3 7 3 3 9 1 6 4 2 3 1 0", ("Eve Stojbs", 42)),
-        ] {
-            let variant = Variant1::default();
-            assert_eq!(calculate_score(line, &variant).unwrap(), expected_result);
-        }
-    }
-
-    #[test]
-    fn test_calculate_score_variant2() {
-        for (line, expected_result) in [
-            (
-                "Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3",
-                ("Yattas Del Lana", 55),
-            ),
-            ("Eve Stojbs 3 7 3 3 9 1 6 4 2 3 1 0", ("Eve Stojbs", 57)),
-        ] {
-            let variant = Variant2::default();
-            assert_eq!(calculate_score(line, &variant).unwrap(), expected_result);
-        }
+fn print_points_standings(standings: &[PointsStanding]) {
+    let name_width = standings.iter().map(|standing| standing.name.chars().count()).max().unwrap_or(0).max(4);
+    println!("{:>4}  {:<name_width$}  {:>6}  {:>3}  {:>3}  {:>3}  {:>5}", "Rank", "Name", "Points", "W", "L", "T", "Pins");
+    for standing in standings {
+        println!("{:>4}  {:<name_width$}  {:>6}  {:>3}  {:>3}  {:>3}  {:>5}",
+                 standing.rank, standing.name, standing.points, standing.won, standing.lost, standing.tied, standing.pins);
     }
+}
 
-    #[test]
-    fn test_calculate_score_variant3() {
-        for (line, expected_result) in [
-            (
-                "Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3",
-                ("Yattas Del Lana", 55),
-            ),
-            ("Eve Stojbs 3 7 3 3 9 1 6 4 2 3 1 0", ("Eve Stojbs", 60)),
-        ] {
-            let variant = Variant3::default();
-            assert_eq!(calculate_score(line, &variant).unwrap(), expected_result);
-        }
+fn print_team_standings(standings: &[TeamStanding], scorecards: &[Scorecard]) {
+    let name_width = standings.iter().map(|standing| standing.name.chars().count()).max().unwrap_or(0).max(4);
+    let scorecard_names = scorecards.iter().map(|scorecard| scorecard.name.as_str()).unique().collect_vec();
+    println!("{:>4}  {:<name_width$}  {:>5}  {:>5}  {:>6}  {}", "Rank", "Team", "Total", "Games", "Blinds",
+             scorecard_names.iter().map(|name| format!("{:>5}", name)).join("  "));
+    for standing in standings {
+        let series = scorecard_names.iter()
+            .map(|name| {
+                let total = standing.series.iter()
+                    .find(|(scorecard, _)| scorecard == name)
+                    .map_or("-".to_owned(), |(_, total)| total.to_string());
+                format!("{:>width$}", total, width = name.chars().count().max(5))
+            })
+            .join("  ");
+        println!("{:>4}  {:<name_width$}  {:>5}  {:>5}  {:>6}  {}", standing.rank, standing.name, standing.total, standing.games, standing.blinds, series);
     }
+}
 
-    #[test]
-    fn test_calculate_score_variant4() {
-        for (line, expected_result) in [
-            (
-                "Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3",
-                ("Yattas Del Lana", 52),
-            ),
-            ("Eve Stojbs 3 7 3 3 9 1 6 4 2 3 1 0", ("Eve Stojbs", 53)),
-        ] {
-            let variant = Variant4::default();
-            assert_eq!(calculate_score(line, &variant).unwrap(), expected_result);
-        }
+/// Print the scores still attainable in a game in progress and, given a target, what it takes to beat it
+fn print_outlook(game: &Game, target: Option<u32>, variant_name: &str, variant: &dyn ScoreCalculator) -> Result<()> {
+    let outlook = outlook(game, variant);
+    match (game.frame(), game.ball()) {
+        (Some(frame), Some(ball)) => println!("Frame {}, ball {}: {} so far under {}", frame, ball, game.total(variant), variant_name),
+        _ => println!("The game is complete with a score of {} under {}", game.total(variant), variant_name),
     }
-
-    #[test]
-    fn test_calculate_score_variant5() {
-        for (line, expected_result) in [
-            (
-                "Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3",
-                ("Yattas Del Lana", 207),
-            ),
-            ("Eve Stojbs 3 7 3 3 9 1 6 4 2 3 1 0", ("Eve Stojbs", 212)),
-        ] {
-            let variant = Variant5::default();
-            assert_eq!(calculate_score(line, &variant).unwrap(), expected_result);
-        }
+    println!("The final score will be between {} and {}", outlook.minimum, outlook.maximum);
+    let Some(target) = target else {
+        return Ok(());
+    };
+    match needed(game, variant, target)? {
+        Some(rolls) if rolls.is_empty() => println!("{} is beaten whatever is bowled", target),
+        Some(rolls) => println!("To beat {} bowl {} ({} pins) and anything after", target, rolls.iter().join(" "),
+                                rolls.iter().map(|pins| *pins as u32).sum::<u32>()),
+        None => println!("{} can't be beaten", target),
     }
+    Ok(())
+}
 
-    #[test]
-    fn test_get_winner() {
-        // Given a scorecard and an aexpected winner
-        for (input, expected_winner) in [
-            ("Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3\nEve Stojbs 3 7 3 3 9 1 6 4 2 3 1 0\n", ("Yattas Del Lana", 45)),
-            ("Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3\nEve Stojbs 3 7 3 3 9 1 6 4 2 3 1 5\n", ("Eve Stojbs", 47)),
-        ] {
-            // And scoring variant 1
-            let variant = Variant1::default();
-
-            // Expect the winner to be as expected
-            assert_eq!(get_winner(&[input], &variant).unwrap(), expected_winner)
-        }
+/// Print the leaderboard with how much every event just rated moved each bowler
+fn print_ratings(ratings: &Ratings, events: &[Event]) {
+    let leaderboard = ratings.leaderboard();
+    let name_width = leaderboard.iter().map(|(name, _)| name.chars().count()).max().unwrap_or(0).max(4);
+    let deviation = ratings.system == RatingSystem::Glicko2;
+    let header = format!("{:>4}  {:<name_width$}  {:>6}  {}{:>5}  {}", "Rank", "Name", "Rating", if deviation { "    RD  " } else { "" }, "Games",
+                         events.iter().map(|event| format!("{:>6}", event.name)).join("  "));
+    println!("{}", header.trim_end());
+    for (index, (name, rating)) in leaderboard.iter().enumerate() {
+        let deltas = events.iter()
+            .map(|event| {
+                let delta = event.delta(name).map_or("-".to_owned(), |delta| format!("{:+.1}", delta));
+                format!("{:>width$}", delta, width = event.name.chars().count().max(6))
+            })
+            .join("  ");
+        let deviation = if deviation { format!("{:>6.1}  ", rating.deviation) } else { String::new() };
+        let row = format!("{:>4}  {:<name_width$}  {:>6.1}  {}{:>5}  {}", index + 1, name, rating.rating, deviation, rating.games, deltas);
+        println!("{}", row.trim_end());
     }
+}
 
-    #[test]
-    fn test_get_winner_variant2() {
-        for input in [
-            "\
-            Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3\n\
-            Eve Stojbs 3 7 3 3 9 1 6 4 2 3 1 0\n\
-            ",
-            "\
-            Eve Stojbs 3 7 3 3 9 1 6 4 2 3 1 0\n\
-            Yattas Del Lana 1 5 3 2 7 3 3 0 10 4 3\n\
-            ",
-        ] {
-            let variant = Variant2::default();
-            assert_eq!(get_winner(&[input], &variant).unwrap(), ("Eve Stojbs", 57))
-        }
+fn print_stats(stats: &[Stats]) {
+    let name_width = stats.iter().map(|stats| stats.name.chars().count()).max().unwrap_or(0).max(4);
+    println!("{:<name_width$}  {:>5}  {:>7}  {:>4}  {:>6}  {:>7}  {:>6}  {:>10}  {:>4}  {:>10}",
+             "Name", "Games", "Average", "High", "Series", "Strike%", "Spare%", "Single pin", "Open", "First ball");
+    for stats in stats {
+        println!("{:<name_width$}  {:>5}  {:>7.1}  {:>4}  {:>6}  {:>7.1}  {:>6.1}  {:>10.1}  {:>4}  {:>10.2}",
+                 stats.name, stats.games, stats.average(), stats.high_game, stats.high_series, stats.strike_percentage(),
+                 stats.spare_percentage(), stats.single_pin_conversion(), stats.open_frames, stats.first_ball_average());
     }
+}
 
-    #[test]
-    fn test_get_winner_multiple_scorecards() {
-        let input = [
-            "\
-            Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3\n\
-            Eve Stojbs 3 7 3 3 9 1 6 4 2 3 1 0\n\
-            ",
-            "\
-            Eve Stojbs 1 1\n\
-            Yattas Del Lana 1 1\n\
-            ",
-        ];
-        let variant = Variant2::default();
-        assert_eq!(get_winner(&input, &variant).unwrap(), ("Eve Stojbs", 59))
+/// Remove `--name value` or `--name=value` from the arguments and return the value
+fn take_option(args: &mut Vec<String>, name: &str) -> Result<Option<String>> {
+    let Some(index) = args.iter().position(|arg| arg == name || arg.starts_with(&format!("{}=", name))) else {
+        return Ok(None);
+    };
+    let arg = args.remove(index);
+    match arg.split_once('=') {
+        Some((_, value)) => Ok(Some(value.to_owned())),
+        None if index < args.len() => Ok(Some(args.remove(index))),
+        None => bail!("Missing value for {}", name),
     }
 }
+
@@ -0,0 +1,323 @@
+use std::cmp::Ordering;
+use std::path::Path;
+use anyhow::{anyhow, bail, Result};
+use serde::Deserialize;
+use crate::config::load_config;
+use crate::scorecard::Scorecard;
+use crate::standings::rank;
+use crate::teams::{team_games, Roster};
+use crate::variants::ScoreCalculator;
+
+/// Who bowls against whom on which scorecard, and how many league points a match is worth, read from a TOML or
+/// JSON schedule file
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Schedule {
+    #[serde(default)]
+    pub points: PointRules,
+    pub matches: Vec<Match>,
+}
+
+/// A head-to-head match between two bowlers, or two teams when the names are teams on the roster
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Match {
+    /// The name of the scorecard the games were bowled on, or just its file name
+    pub scorecard: String,
+    pub home: String,
+    pub away: String,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct PointRules {
+    /// Points for every game won
+    pub game: f64,
+    /// Points for the higher total over all games
+    pub total: f64,
+    pub ties: TieRule,
+}
+
+impl Default for PointRules {
+    fn default() -> Self {
+        PointRules { game: 1.0, total: 1.0, ties: TieRule::Split }
+    }
+}
+
+/// How the points of a tied game or total are awarded
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum TieRule {
+    /// Half the points each
+    #[default]
+    Split,
+    /// All the points to both
+    Both,
+    /// No points to either
+    None,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchResult {
+    pub scorecard: String,
+    pub home: String,
+    pub away: String,
+    /// The home and away score of every game, none for a game that side didn't bowl and so forfeited
+    pub games: Vec<(Option<u32>, Option<u32>)>,
+    pub home_total: u32,
+    pub away_total: u32,
+    pub home_points: f64,
+    pub away_points: f64,
+}
+
+/// A bowler's or team's place by league points
+#[derive(Clone, Debug, PartialEq)]
+pub struct PointsStanding {
+    /// Shared by those with the same points and pins
+    pub rank: usize,
+    pub name: String,
+    pub points: f64,
+    pub matches: usize,
+    /// Matches won, lost and tied on points
+    pub won: usize,
+    pub lost: usize,
+    pub tied: usize,
+    pub pins: u32,
+}
+
+impl Schedule {
+    pub fn load(path: impl AsRef<Path>) -> Result<Schedule> {
+        load_config("schedule", path)
+    }
+}
+
+impl PointRules {
+    /// The home and away points for one comparison
+    fn award(&self, points: f64, outcome: Ordering) -> (f64, f64) {
+        match (outcome, self.ties) {
+            (Ordering::Greater, _) => (points, 0.0),
+            (Ordering::Less, _) => (0.0, points),
+            (Ordering::Equal, TieRule::Split) => (points / 2.0, points / 2.0),
+            (Ordering::Equal, TieRule::Both) => (points, points),
+            (Ordering::Equal, TieRule::None) => (0.0, 0.0),
+        }
+    }
+}
+
+/// Play every match on the schedule, comparing game by game. Names that are teams on the roster bowl as teams. A
+/// side missing games another bowled forfeits them, but every side must be on the match's scorecard.
+pub fn play(schedule: &Schedule, scorecards: &[Scorecard], roster: Option<&Roster>, variant: &dyn ScoreCalculator) -> Result<Vec<MatchResult>> {
+    schedule.matches.iter()
+        .map(|game_match| {
+            let scorecard = scorecards.iter()
+                .find(|scorecard| scorecard.name == game_match.scorecard
+                    || Path::new(&scorecard.name).file_name().is_some_and(|name| *name == *game_match.scorecard))
+                .ok_or_else(|| anyhow!("No scorecard {} for {} against {}", game_match.scorecard, game_match.home, game_match.away))?;
+            let home = side_games(scorecard, &game_match.home, roster, variant);
+            let away = side_games(scorecard, &game_match.away, roster, variant);
+            for (side, games) in [(&game_match.home, &home), (&game_match.away, &away)] {
+                if games.is_empty() {
+                    bail!("{} is not on scorecard {}", side, scorecard.name);
+                }
+            }
+            let games: Vec<_> = (0..home.len().max(away.len()))
+                .map(|game| (home.get(game).copied(), away.get(game).copied()))
+                .collect();
+            let (home_total, away_total) = (home.iter().sum::<u32>(), away.iter().sum::<u32>());
+            let rules = &schedule.points;
+            let (mut home_points, mut away_points) = rules.award(rules.total, home_total.cmp(&away_total));
+            for game in &games {
+                let (home_game_points, away_game_points) = rules.award(rules.game, game.0.cmp(&game.1));
+                home_points += home_game_points;
+                away_points += away_game_points;
+            }
+            Ok(MatchResult {
+                scorecard: scorecard.name.clone(),
+                home: game_match.home.clone(),
+                away: game_match.away.clone(),
+                games,
+                home_total,
+                away_total,
+                home_points,
+                away_points,
+            })
+        })
+        .collect()
+}
+
+/// The score of every game a bowler or team bowled on the scorecard, in order
+fn side_games(scorecard: &Scorecard, name: &str, roster: Option<&Roster>, variant: &dyn ScoreCalculator) -> Vec<u32> {
+    match roster.filter(|roster| roster.teams.iter().any(|team| team.name == name)) {
+        Some(roster) => team_games(std::slice::from_ref(scorecard), roster, variant).into_iter()
+            .filter(|game| game.team == name)
+            .map(|game| game.total)
+            .collect(),
+        None => scorecard.series.iter()
+            .filter(|series| series.name == name)
+            .map(|series| variant.calculate_score(&series.frames))
+            .collect(),
+    }
+}
+
+/// Everyone on the schedule by league points, pins breaking ties
+pub fn points_standings(results: &[MatchResult]) -> Vec<PointsStanding> {
+    let mut standings: Vec<PointsStanding> = Vec::new();
+    for result in results {
+        for (name, points, pins, opponent_points) in [
+            (&result.home, result.home_points, result.home_total, result.away_points),
+            (&result.away, result.away_points, result.away_total, result.home_points),
+        ] {
+            let standing = match standings.iter().position(|standing| standing.name == *name) {
+                Some(index) => &mut standings[index],
+                None => {
+                    standings.push(PointsStanding {
+                        rank: 0,
+                        name: name.clone(),
+                        points: 0.0,
+                        matches: 0,
+                        won: 0,
+                        lost: 0,
+                        tied: 0,
+                        pins: 0,
+                    });
+                    standings.last_mut().unwrap()
+                }
+            };
+            standing.points += points;
+            standing.pins += pins;
+            standing.matches += 1;
+            match points.total_cmp(&opponent_points) {
+                Ordering::Greater => standing.won += 1,
+                Ordering::Less => standing.lost += 1,
+                Ordering::Equal => standing.tied += 1,
+            }
+        }
+    }
+    standings.sort_by(|a, b| b.points.total_cmp(&a.points).then(b.pins.cmp(&a.pins)));
+    rank(&mut standings, |a, b| (a.points, a.pins) == (b.points, b.pins), |standing| &mut standing.rank);
+    standings
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::frame::GameRules;
+    use crate::scorecard::{Notation, Scorecard};
+    use crate::teams::Roster;
+    use crate::variants::Variant1;
+    use super::{play, points_standings, Schedule};
+
+    fn scorecards() -> Vec<Scorecard> {
+        vec![
+            Scorecard::parse("leagues/week1.txt", "\
+                Eve Stojbs 9 0\n\
+                Yattas Del Lana 5 0\n\
+                Eve Stojbs 3 0\n\
+                Yattas Del Lana 3 0\n\
+                Bo Ling 7 0\n\
+                ", Notation::Numeric, GameRules::default()).unwrap(),
+            Scorecard::parse("leagues/week2.txt", "Eve Stojbs 1 0\nBo Ling 8 0\n", Notation::Numeric, GameRules::default()).unwrap(),
+        ]
+    }
+
+    #[test]
+    fn test_play() {
+        // Given a schedule with the default rules, a point per game and for the total, ties split
+        let schedule: Schedule = toml::from_str("\
+            [[matches]]\n\
+            scorecard = \"week1.txt\"\n\
+            home = \"Eve Stojbs\"\n\
+            away = \"Yattas Del Lana\"\n\
+            [[matches]]\n\
+            scorecard = \"week2.txt\"\n\
+            home = \"Bo Ling\"\n\
+            away = \"Eve Stojbs\"\n\
+            ").unwrap();
+
+        // When the matches are played
+        let results = play(&schedule, &scorecards(), None, &Variant1).unwrap();
+
+        // Then points are awarded per game and for the total
+        let points = results.iter()
+            .map(|result| (result.games.clone(), result.home_points, result.away_points))
+            .collect::<Vec<_>>();
+        assert_eq!(points, [
+            (vec![(Some(9), Some(5)), (Some(3), Some(3))], 2.5, 0.5),
+            (vec![(Some(8), Some(1))], 2.0, 0.0),
+        ]);
+
+        // And the standings are by points
+        let standings = points_standings(&results).into_iter()
+            .map(|standing| (standing.rank, standing.name, standing.points, standing.won, standing.lost))
+            .collect::<Vec<_>>();
+        assert_eq!(standings, [
+            (1, "Eve Stojbs".to_owned(), 2.5, 1, 1),
+            (2, "Bo Ling".to_owned(), 2.0, 1, 0),
+            (3, "Yattas Del Lana".to_owned(), 0.5, 0, 1),
+        ]);
+    }
+
+    #[test]
+    fn test_play_rules_and_teams() {
+        // Given teams, no points for the total, full points to both on a tie
+        let roster: Roster = toml::from_str("\
+            [[teams]]\n\
+            name = \"Pin Pals\"\n\
+            bowlers = [\"Eve Stojbs\"]\n\
+            [[teams]]\n\
+            name = \"Gutter Gang\"\n\
+            bowlers = [\"Yattas Del Lana\", \"Bo Ling\"]\n\
+            ").unwrap();
+        let schedule: Schedule = toml::from_str("\
+            points = { game = 2, total = 0, ties = \"both\" }\n\
+            [[matches]]\n\
+            scorecard = \"leagues/week1.txt\"\n\
+            home = \"Pin Pals\"\n\
+            away = \"Gutter Gang\"\n\
+            ").unwrap();
+
+        // When the match is played
+        let results = play(&schedule, &scorecards(), Some(&roster), &Variant1).unwrap();
+
+        // Then the teams' game totals are compared
+        assert_eq!(results[0].games, [(Some(9), Some(12)), (Some(3), Some(3))]);
+        assert_eq!((results[0].home_points, results[0].away_points), (2.0, 4.0));
+    }
+
+    #[test]
+    fn test_play_teams_with_substitute_mid_series() {
+        // Given two teams bowling the same pins, one bringing in a substitute for the last game
+        let roster: Roster = toml::from_str("\
+            blind = 100\n\
+            [[teams]]\n\
+            name = \"Pin Pals\"\n\
+            bowlers = [\"Eve Stojbs\", \"Bo Ling\"]\n\
+            substitutes = [\"Sam Sub\"]\n\
+            [[teams]]\n\
+            name = \"Gutter Gang\"\n\
+            bowlers = [\"Yattas Del Lana\", \"Ally Lane\"]\n\
+            ").unwrap();
+        let scorecards = [Scorecard::parse("week1.txt", "\
+            Eve Stojbs 9 0\nBo Ling 9 0\nYattas Del Lana 9 0\nAlly Lane 9 0\n\
+            Eve Stojbs 9 0\nBo Ling 9 0\nYattas Del Lana 9 0\nAlly Lane 9 0\n\
+            Eve Stojbs 9 0\nSam Sub 9 0\nYattas Del Lana 9 0\nAlly Lane 9 0\n\
+            ", Notation::Numeric, GameRules::default()).unwrap()];
+        let schedule: Schedule = toml::from_str("[[matches]]\nscorecard = \"week1.txt\"\nhome = \"Pin Pals\"\naway = \"Gutter Gang\"").unwrap();
+
+        // When the match is played
+        let results = play(&schedule, &scorecards, Some(&roster), &Variant1).unwrap();
+
+        // Then every game is tied
+        assert_eq!(results[0].games, [(Some(18), Some(18)); 3]);
+        assert_eq!((results[0].home_points, results[0].away_points), (2.0, 2.0));
+    }
+
+    #[test]
+    fn test_play_missing_scorecard() {
+        let schedule: Schedule = toml::from_str("[[matches]]\nscorecard = \"week9.txt\"\nhome = \"A\"\naway = \"B\"").unwrap();
+        assert!(play(&schedule, &scorecards(), None, &Variant1).is_err());
+        let schedule: Schedule = toml::from_str("[[matches]]\nscorecard = \"week1.txt\"\nhome = \"Eve Stojbs\"\naway = \"Yattas Del Lanna\"").unwrap();
+        let error = play(&schedule, &scorecards(), None, &Variant1).unwrap_err();
+        assert_eq!(error.to_string(), "Yattas Del Lanna is not on scorecard leagues/week1.txt");
+        assert!(toml::from_str::<Schedule>("points = { ties = \"coin-toss\" }\nmatches = []").is_err());
+    }
+}
@@ -0,0 +1,171 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+use std::str::FromStr;
+use anyhow::{bail, Error, Result};
+use itertools::Itertools;
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+use crate::config::load_config;
+use crate::scorecard::Scorecard;
+
+/// Which differences between two spellings of a name don't make them different bowlers
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Normalization {
+    /// `eve stojbs` is `Eve Stojbs`
+    pub case: bool,
+    /// `Eve  Stojbs` is `Eve Stojbs`, whitespace is always trimmed
+    pub whitespace: bool,
+    /// `Évé` is `Eve` and compatibility characters like `ﬁ` are `fi`
+    pub unicode: bool,
+    /// `Stojbs, Eve` is `Eve Stojbs`
+    pub comma: bool,
+}
+
+impl FromStr for Normalization {
+    type Err = Error;
+
+    /// A comma separated list of `case`, `whitespace`, `unicode` and `comma`, or `all` or `none`
+    fn from_str(s: &str) -> Result<Self> {
+        let mut normalization = Normalization::default();
+        for rule in s.split(',').map(str::trim) {
+            match rule {
+                "case" => normalization.case = true,
+                "whitespace" => normalization.whitespace = true,
+                "unicode" => normalization.unicode = true,
+                "comma" => normalization.comma = true,
+                "all" => normalization = Normalization { case: true, whitespace: true, unicode: true, comma: true },
+                "none" => normalization = Normalization::default(),
+                _ => bail!("Invalid name normalization {}", rule),
+            }
+        }
+        Ok(normalization)
+    }
+}
+
+impl Normalization {
+    /// The form of a name that is compared to tell bowlers apart
+    pub fn normalize(&self, name: &str) -> String {
+        let mut name = name.trim().to_owned();
+        if self.comma {
+            if let Some((last, first)) = name.split_once(',') {
+                name = format!("{} {}", first.trim(), last.trim());
+            }
+        }
+        if self.unicode {
+            name = name.nfkd().filter(|c| !is_combining_mark(*c)).nfc().collect();
+        }
+        if self.whitespace {
+            name = name.split_whitespace().join(" ");
+        }
+        if self.case {
+            name = name.to_lowercase();
+        }
+        name
+    }
+}
+
+/// Gives every bowler a single name across scorecards, either the canonical ID from an alias file or the first
+/// spelling seen
+#[derive(Clone, Debug, Default)]
+pub struct Names {
+    normalization: Normalization,
+    /// Canonical IDs by normalized name
+    ids: HashMap<String, String>,
+}
+
+impl Names {
+    pub fn new(normalization: Normalization) -> Self {
+        Names { normalization, ids: HashMap::new() }
+    }
+
+    /// Make the name and every alias refer to the bowler with the ID
+    pub fn add_aliases<'a>(&mut self, id: &'a str, aliases: impl IntoIterator<Item = &'a str>) -> Result<()> {
+        for name in [id].into_iter().chain(aliases) {
+            let normalized = self.normalization.normalize(name);
+            match self.ids.get(&normalized) {
+                Some(other) if other != id => bail!("{} is an alias of both {} and {}", name, other, id),
+                _ => self.ids.insert(normalized, id.to_owned()),
+            };
+        }
+        Ok(())
+    }
+
+    /// Read an alias file mapping canonical bowler IDs to the other names they go by, e.g.
+    /// `"Eve Stojbs" = ["Stojbs, Eve", "E. Stojbs"]`
+    pub fn load_aliases(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        let aliases: BTreeMap<String, Vec<String>> = load_config("alias", path)?;
+        for (id, aliases) in aliases {
+            self.add_aliases(&id, aliases.iter().map(String::as_str))?;
+        }
+        Ok(())
+    }
+
+    /// The name to combine a bowler's totals under, remembering names not seen before
+    pub fn resolve(&mut self, name: &str) -> String {
+        self.ids.entry(self.normalization.normalize(name))
+            .or_insert_with(|| name.trim().to_owned())
+            .clone()
+    }
+
+    /// Rename every series on the scorecards to the name its bowler resolves to
+    pub fn apply(&mut self, scorecards: &mut [Scorecard]) {
+        for series in scorecards.iter_mut().flat_map(|scorecard| scorecard.series.iter_mut()) {
+            series.name = self.resolve(&series.name);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use crate::frame::GameRules;
+    use crate::scorecard::{Notation, Scorecard};
+    use crate::standings::standings;
+    use crate::variants::Variant1;
+    use super::{Names, Normalization};
+
+    #[test]
+    fn test_normalize() {
+        for (rules, name, expected_name) in [
+            ("none", " Eve  Stojbs ", "Eve  Stojbs"),
+            ("case", "Eve Stojbs", "eve stojbs"),
+            ("whitespace", "Eve \t Stojbs", "Eve Stojbs"),
+            ("unicode", "Évé Ｓtojbs", "Eve Stojbs"),
+            ("comma", "Stojbs, Eve", "Eve Stojbs"),
+            ("all", " STOJBS,  Évé ", "eve stojbs"),
+        ] {
+            let normalization = Normalization::from_str(rules).unwrap();
+            assert_eq!(normalization.normalize(name), expected_name, "{}", rules);
+        }
+        assert!(Normalization::from_str("case,shouting").is_err());
+    }
+
+    #[test]
+    fn test_names_are_merged() {
+        // Given scorecards from different lanes that spell names differently
+        let mut scorecards = [
+            Scorecard::parse("lane1.txt", "Eve Stojbs 1 1\nYattas Del Lana 5 4\n", Notation::Numeric, GameRules::default()).unwrap(),
+            Scorecard::parse("lane2.txt", "eve  stojbs 2 2\n\"Stojbs, Eve\" 3 3\nYDL 1 0\n", Notation::Numeric, GameRules::default()).unwrap(),
+        ];
+
+        // And an alias for one of the bowlers
+        let mut names = Names::new(Normalization::from_str("all").unwrap());
+        names.add_aliases("Yattas Del Lana", ["YDL"]).unwrap();
+
+        // When the names are resolved
+        names.apply(&mut scorecards);
+
+        // Then each bowler's games are combined under one name
+        let totals = standings(&scorecards, &Variant1, &[]).into_iter()
+            .map(|standing| (standing.name, standing.total, standing.games))
+            .collect::<Vec<_>>();
+        assert_eq!(totals, [("Eve Stojbs".to_owned(), 12, 3), ("Yattas Del Lana".to_owned(), 10, 2)]);
+    }
+
+    #[test]
+    fn test_conflicting_aliases() {
+        let mut names = Names::new(Normalization::from_str("case").unwrap());
+        names.add_aliases("Eve Stojbs", ["E. Stojbs"]).unwrap();
+        assert!(names.add_aliases("Ed Stojbs", ["e. stojbs"]).is_err());
+    }
+}
@@ -0,0 +1,172 @@
+use anyhow::{bail, Result};
+use crate::frame::FRAMES_PER_GAME;
+use crate::game::Game;
+use crate::variants::ScoreCalculator;
+
+/// How many frames from the end of a game the rolls needed to beat a target are worked out for, as the search
+/// grows about eightfold with every frame
+pub const NEEDED_FRAMES: usize = 5;
+
+/// The final scores still attainable from a game in progress
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Outlook {
+    /// The score with gutter balls from here on
+    pub minimum: u32,
+    /// The best score with every rack from here on cleared by a strike, or by a spare after one pin short of a strike
+    pub maximum: u32,
+}
+
+/// The lowest and highest final score of the game. Knocking down more pins never lowers a score under the variants
+/// or rule files, but a spare can be worth more than a strike, so every way of clearing the remaining racks with
+/// strikes and spares is tried for the maximum.
+pub fn outlook(game: &Game, variant: &dyn ScoreCalculator) -> Outlook {
+    let mut minimum = game.clone();
+    while minimum.pins_standing().is_some() {
+        minimum.roll(0).unwrap();
+    }
+    Outlook { minimum: minimum.total(variant), maximum: maximum(game, variant) }
+}
+
+fn maximum(game: &Game, variant: &dyn ScoreCalculator) -> u32 {
+    let Some(standing) = game.pins_standing() else {
+        return game.total(variant);
+    };
+    let strike = game.rules().strike;
+    let choices = if game.is_fresh_rack() && strike > 1 { vec![standing, strike - 1] } else { vec![standing] };
+    choices.into_iter()
+        .map(|pins| {
+            let mut game = game.clone();
+            game.roll(pins).unwrap();
+            maximum(&game, variant)
+        })
+        .max()
+        .unwrap()
+}
+
+/// The remaining rolls with the fewest pins that beat the target, none if it can't be beaten. Every roll after them
+/// may be a gutter ball.
+///
+/// Every way to finish the game is tried, with pins knocked down as early in a frame as possible since every bonus
+/// counts the first ball of a frame, and a spare only made after one pin short of a strike on the first ball. That
+/// is only done for the last frames of a game.
+pub fn needed(game: &Game, variant: &dyn ScoreCalculator, target: u32) -> Result<Option<Vec<u8>>> {
+    if game.frame().is_some_and(|frame| frame + NEEDED_FRAMES <= FRAMES_PER_GAME) {
+        bail!("What is needed can only be worked out for the last {} frames", NEEDED_FRAMES);
+    }
+    let mut best = None;
+    search(game, game.rolls().len(), variant, target, &mut Vec::new(), 0, &mut best);
+    Ok(best.map(|(rolls, _)| rolls))
+}
+
+fn search(game: &Game, given_rolls: usize, variant: &dyn ScoreCalculator, target: u32, rolls: &mut Vec<u8>, pins: u32, best: &mut Option<(Vec<u8>, u32)>) {
+    if best.as_ref().is_some_and(|(_, best_pins)| pins >= *best_pins) {
+        return;
+    }
+    let outlook = outlook(game, variant);
+    if outlook.maximum <= target {
+        return;
+    }
+    if outlook.minimum > target {
+        *best = Some((rolls.clone(), pins));
+        return;
+    }
+    for next in choices(game, given_rolls).into_iter().rev() {
+        let mut game = game.clone();
+        game.roll(next).unwrap();
+        rolls.push(next);
+        search(&game, given_rolls, variant, target, rolls, pins + next as u32, best);
+        rolls.pop();
+    }
+}
+
+/// The pin counts worth trying on the next roll, fewest first
+fn choices(game: &Game, given_rolls: usize) -> Vec<u8> {
+    let Some(standing) = game.pins_standing() else {
+        return Vec::new();
+    };
+    let rules = game.rules();
+    let (frame, ball) = (game.frame().unwrap(), game.ball().unwrap());
+    let previous = game.rolls().last().copied().unwrap_or(0);
+    let previous_given = game.rolls().len() <= given_rolls;
+    // Knocking down more pins than a strike or spare takes counts no different
+    let spare = rules.spare.saturating_sub(10 - standing).min(standing);
+    match (frame, ball) {
+        // Whatever is knocked down on a fresh rack counts for every bonus
+        _ if game.is_fresh_rack() => (0..=rules.strike).collect(),
+        // Spares are made after one pin short of a strike, or after a first ball that was already bowled
+        (1..=9, 2) | (10, 2) if previous_given => (0..=spare).collect(),
+        (1..=9, 2) | (10, 2) if previous + 1 == rules.strike => vec![0, spare],
+        // The second fill ball after a first one that left pins standing counts no different from it
+        (10, 3) if previous_given => (0..=spare).collect(),
+        _ => vec![0],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game::Game;
+    use crate::rules::FrameRules;
+    use crate::variants::{Variant1, Variant4, Variant5};
+    use super::{needed, outlook, Outlook};
+
+    fn game(rolls: &[u8]) -> Game {
+        let mut game = Game::new();
+        for pins in rolls {
+            game.roll(*pins).unwrap();
+        }
+        game
+    }
+
+    #[test]
+    fn test_outlook() {
+        for (rolls, expected_outlook) in [
+            (vec![], Outlook { minimum: 0, maximum: 300 }),
+            (vec![10; 9], Outlook { minimum: 240, maximum: 300 }),
+            (vec![0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 7], Outlook { minimum: 7, maximum: 20 }),
+            (vec![10; 12], Outlook { minimum: 300, maximum: 300 }),
+        ] {
+            assert_eq!(outlook(&game(&rolls), &Variant4::default()), expected_outlook, "{:?}", rolls);
+        }
+        assert_eq!(outlook(&game(&[]), &Variant1), Outlook { minimum: 0, maximum: 100 });
+    }
+
+    #[test]
+    fn test_needed() {
+        // Given eight frames of nine
+        let game = game(&[9, 0, 9, 0, 9, 0, 9, 0, 9, 0, 9, 0, 9, 0, 9, 0]);
+
+        // When the rolls needed to beat a target are worked out
+        for (target, expected_rolls) in [
+            (72, Some(vec![1])),
+            (90, Some(vec![10, 5])),
+            (110, Some(vec![10, 10, 5])),
+            (122, Some(vec![10, 10, 10, 1])),
+            (132, None),
+        ] {
+            // Then they are the ones with the fewest pins
+            assert_eq!(needed(&game, &Variant4::default(), target).unwrap(), expected_rolls, "{}", target);
+        }
+    }
+
+    #[test]
+    fn test_outlook_spare_bonus() {
+        // Given rules where a spare is worth more than a strike, and nine open frames
+        let rules = FrameRules { spare_bonus: 50, ..FrameRules::default() };
+        let game = game(&[9, 0, 9, 0, 9, 0, 9, 0, 9, 0, 9, 0, 9, 0, 9, 0, 9, 0]);
+
+        // When the outlook and what is needed to beat 100 are worked out
+        // Then the spare counts for the maximum
+        assert_eq!(outlook(&game, &rules), Outlook { minimum: 81, maximum: 141 });
+        assert_eq!(needed(&game, &rules, 100).unwrap(), Some(vec![9, 1]));
+    }
+
+    #[test]
+    fn test_needed_in_frame() {
+        // A first ball already bowled can still be spared, and pins count no matter the ball
+        let game = game(&[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 4]);
+        assert_eq!(needed(&game, &Variant4::default(), 6).unwrap(), Some(vec![3]));
+        assert_eq!(needed(&game, &Variant4::default(), 10).unwrap(), Some(vec![6, 1]));
+        assert_eq!(needed(&game, &Variant5::default(), 100).unwrap(), None);
+        assert!(needed(&Game::new(), &Variant4::default(), 100).is_err());
+    }
+}
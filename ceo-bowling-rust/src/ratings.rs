@@ -0,0 +1,361 @@
+use std::collections::BTreeMap;
+use std::f64::consts::PI;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use anyhow::{bail, Context, Error, Result};
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+use crate::config::load_config;
+use crate::scorecard::Scorecard;
+use crate::variants::ScoreCalculator;
+
+/// Points an Elo rating moves for a game against a single opponent that went completely against expectations
+const ELO_K: f64 = 32.0;
+/// How much the Glicko-2 volatility can change from one event to the next
+const GLICKO2_TAU: f64 = 0.5;
+/// The factor between Glicko ratings and the Glicko-2 scale
+const GLICKO2_SCALE: f64 = 173.7178;
+
+/// How ratings are updated
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RatingSystem {
+    /// Updated after every game
+    #[default]
+    Elo,
+    /// Updated after every event, taking every game of it into account at once
+    Glicko2,
+}
+
+impl FromStr for RatingSystem {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "elo" => Ok(RatingSystem::Elo),
+            "glicko2" | "glicko-2" => Ok(RatingSystem::Glicko2),
+            _ => bail!("Invalid rating system {}, expected elo or glicko2", s),
+        }
+    }
+}
+
+impl Display for RatingSystem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            RatingSystem::Elo => write!(f, "elo"),
+            RatingSystem::Glicko2 => write!(f, "glicko2"),
+        }
+    }
+}
+
+/// A bowler's rating, the deviation and volatility only being used by Glicko-2
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Rating {
+    pub rating: f64,
+    pub deviation: f64,
+    pub volatility: f64,
+    pub games: usize,
+}
+
+impl Default for Rating {
+    fn default() -> Self {
+        Rating { rating: 1500.0, deviation: 350.0, volatility: 0.06, games: 0 }
+    }
+}
+
+/// The ratings of every bowler and the events they came from, kept in a TOML or JSON file between runs
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Ratings {
+    pub system: RatingSystem,
+    /// Fingerprints of the games on the scorecards already rated, so that rating one again does nothing
+    #[serde(default)]
+    pub events: Vec<String>,
+    #[serde(default)]
+    pub bowlers: BTreeMap<String, Rating>,
+}
+
+/// How an event changed the ratings of the bowlers in it
+#[derive(Clone, Debug, PartialEq)]
+pub struct Event {
+    pub name: String,
+    /// Every bowler in the event with their rating before and after, in name order
+    pub changes: Vec<(String, f64, f64)>,
+}
+
+impl Event {
+    /// How much the event changed a bowler's rating, none if they weren't in it
+    pub fn delta(&self, bowler: &str) -> Option<f64> {
+        self.changes.iter()
+            .find(|(name, _, _)| name == bowler)
+            .map(|(_, before, after)| after - before)
+    }
+}
+
+impl Ratings {
+    pub fn new(system: RatingSystem) -> Ratings {
+        Ratings { system, ..Ratings::default() }
+    }
+
+    /// Read a ratings file, or start afresh when there is none yet. The system is taken from the file unless one is
+    /// given, which must then match.
+    pub fn load(path: impl AsRef<Path>, system: Option<RatingSystem>) -> Result<Ratings> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Ratings::new(system.unwrap_or_default()));
+        }
+        let ratings: Ratings = load_config("ratings", path)?;
+        match system {
+            Some(system) if system != ratings.system => {
+                bail!("Ratings file {} holds {} ratings, not {}", path.display(), ratings.system, system)
+            }
+            _ => Ok(ratings),
+        }
+    }
+
+    /// Write the ratings file in the format its extension names
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let contents = match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("toml") => toml::to_string(self)?,
+            Some(extension) if extension.eq_ignore_ascii_case("json") => serde_json::to_string_pretty(self)? + "\n",
+            _ => bail!("Unknown ratings file format, expected .toml or .json"),
+        };
+        fs::write(path, contents).with_context(|| format!("Could not write ratings file {}", path.display()))
+    }
+
+    /// Every rated bowler, highest rating first
+    pub fn leaderboard(&self) -> Vec<(&str, &Rating)> {
+        let mut leaderboard: Vec<_> = self.bowlers.iter().map(|(name, rating)| (name.as_str(), rating)).collect();
+        leaderboard.sort_by(|a, b| b.1.rating.total_cmp(&a.1.rating));
+        leaderboard
+    }
+
+    /// Rate the games on a scorecard, every bowler's first game being one match between all of them and so on, and
+    /// every bowler beating those they outscored. A scorecard that has already been rated gives none. That is told
+    /// by its games rather than its name, so the same games read through another path or from standard input
+    /// aren't rated twice.
+    pub fn rate(&mut self, scorecard: &Scorecard, variant: &dyn ScoreCalculator) -> Option<Event> {
+        let fingerprint = fingerprint(scorecard);
+        if self.events.contains(&fingerprint) {
+            return None;
+        }
+        let mut games: Vec<Vec<(&str, u32)>> = Vec::new();
+        let mut bowler_games: BTreeMap<&str, usize> = BTreeMap::new();
+        for series in &scorecard.series {
+            let game = bowler_games.entry(&series.name).or_default();
+            if games.len() == *game {
+                games.push(Vec::new());
+            }
+            games[*game].push((&series.name, variant.calculate_score(&series.frames)));
+            *game += 1;
+        }
+        let before: BTreeMap<_, _> = bowler_games.keys()
+            .map(|name| (*name, self.bowlers.get(*name).copied().unwrap_or_default()))
+            .collect();
+        match self.system {
+            RatingSystem::Elo => games.iter().for_each(|game| self.rate_elo(game)),
+            RatingSystem::Glicko2 => self.rate_glicko2(&games),
+        }
+        for (name, games) in bowler_games {
+            self.bowlers.entry(name.to_owned()).or_default().games += games;
+        }
+        self.events.push(fingerprint);
+        Some(Event {
+            name: scorecard.name.clone(),
+            changes: before.into_iter()
+                .map(|(name, before)| (name.to_owned(), before.rating, self.bowlers[name].rating))
+                .collect(),
+        })
+    }
+
+    /// Move every bowler by the sum of their results against every opponent, each opponent weighing the same
+    fn rate_elo(&mut self, game: &[(&str, u32)]) {
+        if game.len() < 2 {
+            return;
+        }
+        let ratings: Vec<_> = game.iter().map(|(name, _)| self.bowlers.get(*name).map_or(1500.0, |rating| rating.rating)).collect();
+        let k = ELO_K / (game.len() - 1) as f64;
+        for (index, (name, score)) in game.iter().enumerate() {
+            let change: f64 = game.iter()
+                .zip(&ratings)
+                .filter(|((opponent, _), _)| opponent != name)
+                .map(|((_, opponent_score), opponent_rating)| {
+                    let expected = 1.0 / (1.0 + 10f64.powf((opponent_rating - ratings[index]) / 400.0));
+                    result(*score, *opponent_score) - expected
+                })
+                .sum();
+            self.bowlers.entry((*name).to_owned()).or_default().rating += k * change;
+        }
+    }
+
+    /// Rate the event as one Glicko-2 rating period. Rated bowlers that weren't in it become less certain.
+    fn rate_glicko2(&mut self, games: &[Vec<(&str, u32)>]) {
+        let mut results: BTreeMap<&str, Vec<(Rating, f64)>> = BTreeMap::new();
+        for game in games {
+            for (name, score) in game {
+                let opponents = game.iter()
+                    .filter(|(opponent, _)| opponent != name)
+                    .map(|(opponent, opponent_score)| {
+                        (self.bowlers.get(*opponent).copied().unwrap_or_default(), result(*score, *opponent_score))
+                    });
+                results.entry(name).or_default().extend(opponents);
+            }
+        }
+        for name in results.keys() {
+            self.bowlers.entry((*name).to_owned()).or_default();
+        }
+        for (name, rating) in &mut self.bowlers {
+            *rating = glicko2(rating, results.get(name.as_str()).map_or(&[], Vec::as_slice));
+        }
+    }
+}
+
+/// A hash of every bowler's frames on the scorecard, FNV-1a so that it stays the same between builds
+fn fingerprint(scorecard: &Scorecard) -> String {
+    let games = scorecard.series.iter()
+        .map(|series| format!("{}\t{}\n", series.name, series.frames.iter().join(" ")))
+        .collect::<String>();
+    let hash = games.bytes().fold(0xcbf29ce484222325u64, |hash, byte| (hash ^ byte as u64).wrapping_mul(0x100000001b3));
+    format!("{:016x}", hash)
+}
+
+/// A win, tie or loss as 1, ½ or 0
+fn result(score: u32, opponent_score: u32) -> f64 {
+    match score.cmp(&opponent_score) {
+        std::cmp::Ordering::Greater => 1.0,
+        std::cmp::Ordering::Equal => 0.5,
+        std::cmp::Ordering::Less => 0.0,
+    }
+}
+
+/// The rating after a rating period with the results against every opponent, following Glickman's description. Without
+/// any results only the deviation grows.
+fn glicko2(rating: &Rating, results: &[(Rating, f64)]) -> Rating {
+    let mu = (rating.rating - 1500.0) / GLICKO2_SCALE;
+    let phi = rating.deviation / GLICKO2_SCALE;
+    if results.is_empty() {
+        return Rating { deviation: GLICKO2_SCALE * phi.hypot(rating.volatility), ..*rating };
+    }
+    let g = |phi: f64| 1.0 / (1.0 + 3.0 * phi * phi / (PI * PI)).sqrt();
+    let outcomes: Vec<_> = results.iter()
+        .map(|(opponent, result)| {
+            let opponent_phi = opponent.deviation / GLICKO2_SCALE;
+            let opponent_mu = (opponent.rating - 1500.0) / GLICKO2_SCALE;
+            let expected = 1.0 / (1.0 + (-g(opponent_phi) * (mu - opponent_mu)).exp());
+            (g(opponent_phi), expected, result)
+        })
+        .collect();
+    let v = 1.0 / outcomes.iter().map(|(g, expected, _)| g * g * expected * (1.0 - expected)).sum::<f64>();
+    let improvement: f64 = outcomes.iter().map(|(g, expected, result)| g * (*result - expected)).sum();
+    let delta = v * improvement;
+
+    // The new volatility by the Illinois algorithm
+    let a = (rating.volatility * rating.volatility).ln();
+    let f = |x: f64| {
+        let ex = x.exp();
+        ex * (delta * delta - phi * phi - v - ex) / (2.0 * (phi * phi + v + ex).powi(2)) - (x - a) / (GLICKO2_TAU * GLICKO2_TAU)
+    };
+    let mut low = a;
+    let mut high = if delta * delta > phi * phi + v {
+        (delta * delta - phi * phi - v).ln()
+    } else {
+        let mut k = 1.0;
+        while f(a - k * GLICKO2_TAU) < 0.0 {
+            k += 1.0;
+        }
+        a - k * GLICKO2_TAU
+    };
+    let (mut f_low, mut f_high) = (f(low), f(high));
+    while (high - low).abs() > 0.000001 {
+        let c = low + (low - high) * f_low / (f_high - f_low);
+        let f_c = f(c);
+        if f_c * f_high <= 0.0 {
+            (low, f_low) = (high, f_high);
+        } else {
+            f_low /= 2.0;
+        }
+        (high, f_high) = (c, f_c);
+    }
+    let volatility = (low / 2.0).exp();
+
+    let phi = 1.0 / (1.0 / (phi * phi + volatility * volatility) + 1.0 / v).sqrt();
+    Rating {
+        rating: 1500.0 + GLICKO2_SCALE * (mu + phi * phi * improvement),
+        deviation: GLICKO2_SCALE * phi,
+        volatility,
+        games: rating.games,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::frame::GameRules;
+    use crate::scorecard::{Notation, Scorecard};
+    use crate::variants::Variant1;
+    use super::{glicko2, Rating, RatingSystem, Ratings};
+
+    fn scorecard() -> Scorecard {
+        Scorecard::parse("week1.txt", "\
+            Eve Stojbs 9 0\n\
+            Bo Ling 5 0\n\
+            Yattas Del Lana 5 0\n\
+            Eve Stojbs 3 0\n\
+            Bo Ling 7 0\n\
+            ", Notation::Numeric, GameRules::default()).unwrap()
+    }
+
+    #[test]
+    fn test_elo() {
+        // Given new bowlers
+        let mut ratings = Ratings::new(RatingSystem::Elo);
+
+        // When two games are rated, one between three bowlers and one between two
+        let event = ratings.rate(&scorecard(), &Variant1).unwrap();
+
+        // Then every game moves the ratings by a K of 32 shared between the opponents, the second game starting from
+        // the ratings after the first
+        let expected_second_game = 32.0 / (1.0 + 10f64.powf(-24.0 / 400.0));
+        for (name, expected_delta) in [("Eve Stojbs", 16.0 - expected_second_game), ("Bo Ling", -8.0 + expected_second_game), ("Yattas Del Lana", -8.0)] {
+            let delta = event.delta(name).unwrap();
+            assert!((delta - expected_delta).abs() < 0.000001, "{} {}", name, delta);
+        }
+        assert_eq!(event.changes.iter().map(|(_, before, after)| after - before).sum::<f64>().round(), 0.0);
+        assert_eq!(ratings.bowlers["Eve Stojbs"].games, 2);
+
+        // And rating the same scorecard again does nothing, even under another name
+        assert_eq!(ratings.rate(&scorecard(), &Variant1), None);
+        let renamed = Scorecard { name: "./week1.txt".to_owned(), ..scorecard() };
+        assert_eq!(ratings.rate(&renamed, &Variant1), None);
+        assert_eq!(ratings.leaderboard().iter().map(|(name, _)| *name).collect::<Vec<_>>(), ["Bo Ling", "Eve Stojbs", "Yattas Del Lana"]);
+    }
+
+    #[test]
+    fn test_glicko2() {
+        // The example from Glickman's description of Glicko-2
+        let rating = Rating { rating: 1500.0, deviation: 200.0, volatility: 0.06, games: 0 };
+        let opponent = |rating, deviation| Rating { rating, deviation, ..Rating::default() };
+        let rating = glicko2(&rating, &[(opponent(1400.0, 30.0), 1.0), (opponent(1550.0, 100.0), 0.0), (opponent(1700.0, 300.0), 0.0)]);
+        assert!((rating.rating - 1464.06).abs() < 0.01, "{}", rating.rating);
+        assert!((rating.deviation - 151.52).abs() < 0.01, "{}", rating.deviation);
+        assert!((rating.volatility - 0.05999).abs() < 0.00001, "{}", rating.volatility);
+
+        // A bowler sitting out an event becomes less certain
+        let mut ratings = Ratings::new(RatingSystem::Glicko2);
+        ratings.rate(&scorecard(), &Variant1).unwrap();
+        let deviation = ratings.bowlers["Yattas Del Lana"].deviation;
+        ratings.rate(&Scorecard::parse("week2.txt", "Eve Stojbs 9 0\nBo Ling 5 0\n", Notation::Numeric, GameRules::default()).unwrap(), &Variant1).unwrap();
+        assert!(ratings.bowlers["Yattas Del Lana"].deviation > deviation);
+    }
+
+    #[test]
+    fn test_ratings_file() {
+        let mut ratings = Ratings::new(RatingSystem::Glicko2);
+        ratings.rate(&scorecard(), &Variant1).unwrap();
+        let toml = toml::to_string(&ratings).unwrap();
+        assert_eq!(toml::from_str::<Ratings>(&toml).unwrap(), ratings);
+        assert!(toml::from_str::<Ratings>("system = \"trueskill\"").is_err());
+    }
+}
@@ -0,0 +1,136 @@
+use std::path::Path;
+use std::rc::Rc;
+use anyhow::{anyhow, bail, Result};
+use crate::rules::RuleFile;
+use crate::variants::{ScoreCalculator, Variant1, Variant2, Variant3, Variant4, Variant5};
+
+/// A scoring variant that can be selected by name
+pub struct Entry {
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub description: String,
+    pub variant: Rc<dyn ScoreCalculator>,
+}
+
+/// The scoring variants that can be selected by name, built-in ones as well as ones loaded from rule files or
+/// registered by library users
+pub struct Registry {
+    entries: Vec<Entry>,
+}
+
+impl Default for Registry {
+    /// A registry with the built-in variants, selectable as `variant1` or just `1` and so on
+    fn default() -> Self {
+        let mut registry = Registry::empty();
+        for (number, description, variant) in [
+            ("1", "Pins knocked down, strikes and spares count as 10", Rc::new(Variant1) as Rc<dyn ScoreCalculator>),
+            ("2", "Strikes and spares earn a fixed bonus of 10 and 5", Rc::new(Variant2::default())),
+            ("3", "Strike and spare bonuses start at 10 and 5 and grow by 2 and 1 each time they are earned", Rc::new(Variant3::default())),
+            ("4", "Traditional scoring, strikes and spares add the pins of the next two and one rolls", Rc::new(Variant4::default())),
+            ("5", "The sum of variants 1 to 4", Rc::new(Variant5::default())),
+        ] {
+            registry.entries.push(Entry {
+                name: format!("variant{}", number),
+                aliases: vec![number.to_owned()],
+                description: description.to_owned(),
+                variant,
+            });
+        }
+        registry
+    }
+}
+
+impl Registry {
+    pub fn empty() -> Self {
+        Registry { entries: Vec::new() }
+    }
+
+    pub fn register(&mut self, name: impl Into<String>, description: impl Into<String>, variant: impl ScoreCalculator + 'static) -> Result<()> {
+        let name = name.into();
+        if self.get(&name).is_some() {
+            bail!("Scoring variant {} is already registered", name);
+        }
+        self.entries.push(Entry {
+            name,
+            aliases: Vec::new(),
+            description: description.into(),
+            variant: Rc::new(variant),
+        });
+        Ok(())
+    }
+
+    /// Register the variant in a rule file under the name given in the file, or the file name without extension
+    pub fn load(&mut self, path: impl AsRef<Path>) -> Result<&Entry> {
+        let path = path.as_ref();
+        let rule_file = RuleFile::load(path)?;
+        let name = match rule_file.name {
+            Some(name) => name,
+            None => path.file_stem()
+                .and_then(|stem| stem.to_str())
+                .ok_or_else(|| anyhow!("Rule file {} needs a name", path.display()))?
+                .to_owned(),
+        };
+        let description = rule_file.description.unwrap_or_else(|| format!("Rules from {}", path.display()));
+        let variant = rule_file.rules.into_variant(self)?;
+        self.register(name, description, variant)?;
+        Ok(self.entries.last().unwrap())
+    }
+
+    /// Find a variant by name or alias, ignoring case
+    pub fn get(&self, name: &str) -> Option<&Entry> {
+        self.entries.iter().find(|entry| entry.name.eq_ignore_ascii_case(name)
+            || entry.aliases.iter().any(|alias| alias.eq_ignore_ascii_case(name)))
+    }
+
+    pub fn variant(&self, name: &str) -> Result<Rc<dyn ScoreCalculator>> {
+        self.get(name)
+            .map(|entry| entry.variant.clone())
+            .ok_or_else(|| anyhow!("Invalid scoring variant {}", name))
+    }
+
+    pub fn entries(&self) -> &[Entry] {
+        &self.entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::frame::Frame;
+    use crate::variants::ScoreCalculator;
+    use super::Registry;
+
+    struct Pins;
+
+    impl ScoreCalculator for Pins {
+        fn calculate_score(&self, series: &[Frame]) -> u32 {
+            series.iter()
+                .flat_map(|frame| frame.rolls())
+                .map(u32::from)
+                .sum()
+        }
+    }
+
+    #[test]
+    fn test_builtin_variants() {
+        let registry = Registry::default();
+        for name in ["variant1", "Variant2", "3", "VARIANT4", "5"] {
+            assert!(registry.get(name).is_some(), "{}", name);
+        }
+        assert!(registry.get("variant6").is_none());
+    }
+
+    #[test]
+    fn test_register_variant() {
+        // Given a registry with a user variant
+        let mut registry = Registry::default();
+        registry.register("pins", "Every pin counts, fill balls too", Pins).unwrap();
+
+        // Expect it to be selectable by name
+        let series = [Frame::Strike, Frame::Regular(3, 4)];
+        assert_eq!(registry.variant("Pins").unwrap().calculate_score(&series), 17);
+
+        // And names to be unique
+        assert!(registry.register("pins", "Again", Pins).is_err());
+        assert!(registry.register("1", "Clashes with an alias", Pins).is_err());
+    }
+}
@@ -0,0 +1,301 @@
+//! Results in a form other programs can read.
+//!
+//! The JSON schema is the serialized form of the report structs below, field names as written. New fields
+//! may be added, but existing ones are not renamed or removed. CSV output has one row per standing or frame
+//! with the columns listed on the `write_csv` methods, always starting with a header row.
+
+use std::io::Write;
+use std::str::FromStr;
+use anyhow::{bail, Result};
+use serde::Serialize;
+use crate::handicap::{Handicap, HandicapStanding};
+use crate::scorecard::Scorecard;
+use crate::standings::Standing;
+use crate::variants::ScoreCalculator;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    #[default]
+    Text,
+    Json,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            _ if s.eq_ignore_ascii_case("text") => Ok(Format::Text),
+            _ if s.eq_ignore_ascii_case("json") => Ok(Format::Json),
+            _ if s.eq_ignore_ascii_case("csv") => Ok(Format::Csv),
+            _ => bail!("Invalid format {}, expected text, json or csv", s),
+        }
+    }
+}
+
+/// The standings under one variant together with the winner
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct StandingsReport {
+    pub variant: String,
+    /// Everyone ranked first, more than one on a tie and none without bowlers
+    pub winners: Vec<String>,
+    pub winning_score: Option<u32>,
+    pub standings: Vec<Standing>,
+}
+
+/// Handicap standings under one variant, with the scratch total and rank of every bowler
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct HandicapReport {
+    pub variant: String,
+    pub handicap: Handicap,
+    /// Everyone ranked first including handicap
+    pub winners: Vec<String>,
+    pub winning_score: Option<u32>,
+    pub standings: Vec<HandicapStanding>,
+}
+
+/// Every series scored frame by frame under one variant
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FramesReport {
+    pub variant: String,
+    pub series: Vec<SeriesReport>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SeriesReport {
+    pub scorecard: String,
+    pub name: String,
+    pub total: u32,
+    pub frames: Vec<FrameReport>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct FrameReport {
+    /// Counting from 1
+    pub frame: usize,
+    /// Pins knocked down by every roll of the frame, including fill balls
+    pub rolls: Vec<u8>,
+    /// The rolls in traditional notation, e.g. `X` or `7/`
+    pub marks: String,
+    pub base: u32,
+    pub bonus: u32,
+    pub points: u32,
+    pub total: u32,
+}
+
+impl StandingsReport {
+    pub fn new(variant: &str, standings: Vec<Standing>) -> Self {
+        let winners: Vec<_> = standings.iter().take_while(|standing| standing.rank == 1).collect();
+        StandingsReport {
+            variant: variant.to_owned(),
+            winners: winners.iter().map(|standing| standing.name.clone()).collect(),
+            winning_score: winners.first().map(|standing| standing.total),
+            standings,
+        }
+    }
+
+    pub fn write_json(&self, mut writer: impl Write) -> Result<()> {
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writeln!(writer)?;
+        Ok(())
+    }
+
+    /// Columns: `rank,name,total,games,winner`, the last being `true` or `false`
+    pub fn write_csv(&self, writer: impl Write) -> Result<()> {
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.write_record(["rank", "name", "total", "games", "winner"])?;
+        for standing in &self.standings {
+            writer.write_record([
+                standing.rank.to_string(),
+                standing.name.clone(),
+                standing.total.to_string(),
+                standing.games.to_string(),
+                self.winners.contains(&standing.name).to_string(),
+            ])?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl HandicapReport {
+    pub fn new(variant: &str, handicap: Handicap, standings: Vec<HandicapStanding>) -> Self {
+        let winners: Vec<_> = standings.iter().take_while(|standing| standing.rank == 1).collect();
+        HandicapReport {
+            variant: variant.to_owned(),
+            handicap,
+            winners: winners.iter().map(|standing| standing.name.clone()).collect(),
+            winning_score: winners.first().map(|standing| standing.total),
+            standings,
+        }
+    }
+
+    pub fn write_json(&self, mut writer: impl Write) -> Result<()> {
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writeln!(writer)?;
+        Ok(())
+    }
+
+    /// Columns: `rank,name,games,average,handicap,scratch_rank,scratch,total,winner`
+    pub fn write_csv(&self, writer: impl Write) -> Result<()> {
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.write_record(["rank", "name", "games", "average", "handicap", "scratch_rank", "scratch", "total", "winner"])?;
+        for standing in &self.standings {
+            writer.write_record([
+                standing.rank.to_string(),
+                standing.name.clone(),
+                standing.games.to_string(),
+                standing.average.to_string(),
+                standing.handicap.to_string(),
+                standing.scratch_rank.to_string(),
+                standing.scratch.to_string(),
+                standing.total.to_string(),
+                self.winners.contains(&standing.name).to_string(),
+            ])?;
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+impl FramesReport {
+    pub fn new(variant_name: &str, scorecards: &[Scorecard], variant: &dyn ScoreCalculator) -> Self {
+        FramesReport {
+            variant: variant_name.to_owned(),
+            series: scorecards.iter()
+                .flat_map(|scorecard| scorecard.series.iter().map(move |series| (scorecard, series)))
+                .map(|(scorecard, series)| {
+                    let frame_scores = variant.score_frames(&series.frames);
+                    SeriesReport {
+                        scorecard: scorecard.name.clone(),
+                        name: series.name.clone(),
+                        total: frame_scores.last().map_or(0, |frame_score| frame_score.total),
+                        frames: frame_scores.iter()
+                            .enumerate()
+                            .map(|(index, frame_score)| FrameReport {
+                                frame: index + 1,
+                                rolls: frame_score.frame.rolls(),
+                                marks: frame_score.frame.to_string(),
+                                base: frame_score.base,
+                                bonus: frame_score.bonus,
+                                points: frame_score.base + frame_score.bonus,
+                                total: frame_score.total,
+                            })
+                            .collect(),
+                    }
+                })
+                .collect(),
+        }
+    }
+
+    pub fn write_json(&self, mut writer: impl Write) -> Result<()> {
+        serde_json::to_writer_pretty(&mut writer, self)?;
+        writeln!(writer)?;
+        Ok(())
+    }
+
+    /// Columns: `scorecard,name,frame,marks,base,bonus,points,total`
+    pub fn write_csv(&self, writer: impl Write) -> Result<()> {
+        let mut writer = csv::Writer::from_writer(writer);
+        writer.write_record(["scorecard", "name", "frame", "marks", "base", "bonus", "points", "total"])?;
+        for series in &self.series {
+            for frame in &series.frames {
+                writer.write_record([
+                    series.scorecard.clone(),
+                    series.name.clone(),
+                    frame.frame.to_string(),
+                    frame.marks.clone(),
+                    frame.base.to_string(),
+                    frame.bonus.to_string(),
+                    frame.points.to_string(),
+                    frame.total.to_string(),
+                ])?;
+            }
+        }
+        writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use serde_json::json;
+    use crate::frame::GameRules;
+    use crate::scorecard::{Notation, Scorecard};
+    use crate::standings::standings;
+    use crate::variants::Variant4;
+    use super::{Format, FramesReport, StandingsReport};
+
+    fn scorecards() -> Vec<Scorecard> {
+        vec![Scorecard::parse("monday.txt", "Eve Stojbs 10 3 4\nBo Ling 7 3 1 1\n", Notation::Numeric, GameRules::default()).unwrap()]
+    }
+
+    #[test]
+    fn test_standings_report() {
+        // Given the standings of a scorecard
+        let scorecards = scorecards();
+        let report = StandingsReport::new("variant4", standings(&scorecards, &Variant4::default(), &[]));
+
+        // Expect the JSON to follow the schema
+        let mut output = Vec::new();
+        report.write_json(&mut output).unwrap();
+        assert_eq!(serde_json::from_slice::<serde_json::Value>(&output).unwrap(), json!({
+            "variant": "variant4",
+            "winners": ["Eve Stojbs"],
+            "winning_score": 24,
+            "standings": [
+                {"rank": 1, "name": "Eve Stojbs", "total": 24, "games": 1, "subtotals": [{"scorecard": "monday.txt", "total": 24, "games": 1}]},
+                {"rank": 2, "name": "Bo Ling", "total": 13, "games": 1, "subtotals": [{"scorecard": "monday.txt", "total": 13, "games": 1}]},
+            ],
+        }));
+
+        // And the CSV to have a row per bowler
+        let mut output = Vec::new();
+        report.write_csv(&mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "\
+            rank,name,total,games,winner\n\
+            1,Eve Stojbs,24,1,true\n\
+            2,Bo Ling,13,1,false\n\
+            ");
+    }
+
+    #[test]
+    fn test_frames_report() {
+        // Given the frame scores of a scorecard
+        let report = FramesReport::new("variant4", &scorecards(), &Variant4::default());
+
+        // Expect the JSON to follow the schema
+        let mut output = Vec::new();
+        report.write_json(&mut output).unwrap();
+        assert_eq!(serde_json::from_slice::<serde_json::Value>(&output).unwrap()["series"][0], json!({
+            "scorecard": "monday.txt",
+            "name": "Eve Stojbs",
+            "total": 24,
+            "frames": [
+                {"frame": 1, "rolls": [10], "marks": "X", "base": 10, "bonus": 7, "points": 17, "total": 17},
+                {"frame": 2, "rolls": [3, 4], "marks": "34", "base": 7, "bonus": 0, "points": 7, "total": 24},
+            ],
+        }));
+
+        // And the CSV to have a row per frame
+        let mut output = Vec::new();
+        report.write_csv(&mut output).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "\
+            scorecard,name,frame,marks,base,bonus,points,total\n\
+            monday.txt,Eve Stojbs,1,X,10,7,17,17\n\
+            monday.txt,Eve Stojbs,2,34,7,0,7,24\n\
+            monday.txt,Bo Ling,1,7/,10,1,11,11\n\
+            monday.txt,Bo Ling,2,11,2,0,2,13\n\
+            ");
+    }
+
+    #[test]
+    fn test_format() {
+        for (format, expected_format) in [("text", Format::Text), ("JSON", Format::Json), ("Csv", Format::Csv)] {
+            assert_eq!(Format::from_str(format).unwrap(), expected_format);
+        }
+        assert!(Format::from_str("xml").is_err());
+    }
+}
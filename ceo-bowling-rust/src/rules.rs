@@ -0,0 +1,217 @@
+use std::path::Path;
+use anyhow::Result;
+use serde::Deserialize;
+use crate::config::load_config;
+use crate::frame::{Frame, TenthFrame};
+use crate::registry::Registry;
+use crate::variants::{sum_frame_scores, with_running_totals, BonusSource, FrameScore, ScoreCalculator};
+
+/// A rule file, with an optional name and description to list the variant under
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+pub struct RuleFile {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    #[serde(flatten)]
+    pub rules: Rules,
+}
+
+/// A scoring variant described in a TOML or JSON rule file
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum Rules {
+    /// Another variant by name, e.g. `variant2` inside `compose`
+    Named(String),
+    Composite(CompositeRules),
+    Frame(FrameRules),
+}
+
+/// The sum of the scores of several variants
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CompositeRules {
+    pub compose: Vec<Rules>,
+}
+
+/// Frame by frame scoring where strikes and spares can earn a fixed bonus that grows every time it is
+/// earned, and the pins of the following rolls
+#[derive(Clone, Debug, Deserialize, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct FrameRules {
+    /// Points for every pin knocked down
+    pub pin_value: u32,
+    pub spare_bonus: u32,
+    pub spare_increment: u32,
+    pub strike_bonus: u32,
+    pub strike_increment: u32,
+    /// Number of following rolls whose pins are added to a spare
+    pub spare_lookahead: usize,
+    /// Number of following rolls whose pins are added to a strike
+    pub strike_lookahead: usize,
+}
+
+impl Default for FrameRules {
+    fn default() -> Self {
+        FrameRules {
+            pin_value: 1,
+            spare_bonus: 0,
+            spare_increment: 0,
+            strike_bonus: 0,
+            strike_increment: 0,
+            spare_lookahead: 0,
+            strike_lookahead: 0,
+        }
+    }
+}
+
+impl ScoreCalculator for FrameRules {
+    fn calculate_score(&self, series: &[Frame]) -> u32 {
+        self.score_frames(series).last().map_or(0, |frame_score| frame_score.total)
+    }
+
+    fn score_frames(&self, series: &[Frame]) -> Vec<FrameScore> {
+        let rolls: Vec<u32> = series.iter()
+            .flat_map(|frame| frame.rolls())
+            .map(u32::from)
+            .collect();
+        let mut next_roll = 0;
+        let mut spare_bonus = self.spare_bonus;
+        let mut strike_bonus = self.strike_bonus;
+        with_running_totals(series, series.iter().map(|frame| {
+            let (own_rolls, bonus, lookahead) = match frame {
+                Frame::Regular(_, _) | Frame::Tenth(TenthFrame::Regular(_, _)) => (2, 0, 0),
+                Frame::Spare(_) | Frame::Tenth(TenthFrame::Spare(_, _)) => {
+                    spare_bonus += self.spare_increment;
+                    (2, spare_bonus - self.spare_increment, self.spare_lookahead)
+                }
+                Frame::Strike | Frame::Tenth(TenthFrame::Strike(_, _)) => {
+                    strike_bonus += self.strike_increment;
+                    (1, strike_bonus - self.strike_increment, self.strike_lookahead)
+                }
+            };
+            let pins: u32 = rolls.iter().skip(next_roll).take(own_rolls).sum();
+            let lookahead_pins: u32 = rolls.iter().skip(next_roll + own_rolls).take(lookahead).sum();
+            next_roll += own_rolls;
+            let bonus_source = match (bonus, lookahead) {
+                (0, 0) => BonusSource::None,
+                (_, 0) => BonusSource::Fixed,
+                (0, lookahead) => BonusSource::NextRolls(lookahead),
+                _ => BonusSource::Other,
+            };
+            (pins * self.pin_value, bonus + lookahead_pins * self.pin_value, bonus_source)
+        }))
+    }
+}
+
+/// A variant that adds up the scores of other variants, named after the variant they refer to or the kind of rules
+pub struct Composition(pub Vec<(String, Box<dyn ScoreCalculator>)>);
+
+impl ScoreCalculator for Composition {
+    fn calculate_score(&self, series: &[Frame]) -> u32 {
+        self.0.iter()
+            .map(|(_, variant)| variant.calculate_score(series))
+            .sum()
+    }
+
+    fn score_frames(&self, series: &[Frame]) -> Vec<FrameScore> {
+        sum_frame_scores(series, &self.0.iter().map(|(_, variant)| variant.as_ref()).collect::<Vec<_>>())
+    }
+
+    fn parts(&self) -> Vec<(&str, &dyn ScoreCalculator)> {
+        self.0.iter()
+            .map(|(name, variant)| (name.as_str(), variant.as_ref()))
+            .collect()
+    }
+}
+
+impl RuleFile {
+    pub fn load(path: impl AsRef<Path>) -> Result<RuleFile> {
+        load_config("rule", path)
+    }
+}
+
+impl Rules {
+    /// Build the variant, looking up named variants in the registry
+    pub fn into_variant(self, registry: &Registry) -> Result<Box<dyn ScoreCalculator>> {
+        Ok(match self {
+            Rules::Named(name) => Box::new(registry.variant(&name)?),
+            Rules::Composite(CompositeRules { compose }) => Box::new(Composition(compose.into_iter()
+                .map(|rules| {
+                    let name = match &rules {
+                        Rules::Named(name) => name.clone(),
+                        Rules::Composite(_) => "compose".to_owned(),
+                        Rules::Frame(_) => "rules".to_owned(),
+                    };
+                    Ok((name, rules.into_variant(registry)?))
+                })
+                .collect::<Result<Vec<_>>>()?)),
+            Rules::Frame(rules) => Box::new(rules),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::frame::GameRules;
+    use crate::registry::Registry;
+    use crate::scorecard::{parse_line, Notation};
+    use super::{FrameRules, RuleFile, Rules};
+
+    const LINES: [&str; 4] = [
+        "Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3",
+        "Eve Stojbs 3 7 3 3 9 1 6 4 2 3 1 0",
+        "Perfect Game 10 10 10 10 10 10 10 10 10 10 10 10",
+        "Bo Ling 10 7 3 9 0 0 8 10 10 8 1 8 2 0 0 9 1 10",
+    ];
+
+    #[test]
+    fn test_rules_match_builtin_variants() {
+        for (name, rules) in [
+            ("1", "pin_value = 1"),
+            ("2", "spare_bonus = 5\nstrike_bonus = 10"),
+            ("3", "spare_bonus = 5\nspare_increment = 1\nstrike_bonus = 10\nstrike_increment = 2"),
+            ("4", "spare_lookahead = 1\nstrike_lookahead = 2"),
+            ("5", "compose = [\"variant1\", \"variant2\", \"variant3\", { spare_lookahead = 1, strike_lookahead = 2 }]"),
+        ] {
+            // Given a rule file describing a built-in variant
+            let registry = Registry::default();
+            let variant = toml::from_str::<Rules>(rules).unwrap().into_variant(&registry).unwrap();
+            let builtin = registry.variant(name).unwrap();
+
+            // Expect it to score the same as the built-in variant
+            for line in LINES {
+                let (_, series) = parse_line(line, Notation::Numeric, GameRules::default()).unwrap();
+                assert_eq!(variant.calculate_score(&series), builtin.calculate_score(&series), "variant {} {}", name, line);
+            }
+        }
+    }
+
+    #[test]
+    fn test_rules_from_json() {
+        let rules: Rules = serde_json::from_str(r#"{"pin_value": 2, "strike_bonus": 1}"#).unwrap();
+        assert_eq!(rules, Rules::Frame(FrameRules { pin_value: 2, strike_bonus: 1, ..FrameRules::default() }));
+
+        let (_, series) = parse_line("Eve Stojbs 10 3 4", Notation::Numeric, GameRules::default()).unwrap();
+        assert_eq!(rules.into_variant(&Registry::default()).unwrap().calculate_score(&series), 35);
+    }
+
+    #[test]
+    fn test_rule_file() {
+        let rule_file: RuleFile = toml::from_str("\
+            name = \"double\"\n\
+            description = \"Every pin counts twice\"\n\
+            pin_value = 2\n\
+            ").unwrap();
+        assert_eq!(rule_file, RuleFile {
+            name: Some("double".to_owned()),
+            description: Some("Every pin counts twice".to_owned()),
+            rules: Rules::Frame(FrameRules { pin_value: 2, ..FrameRules::default() }),
+        });
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        assert!(toml::from_str::<RuleFile>("spare_bonus = 5\nstrike_bonnus = 10").is_err());
+        assert!(toml::from_str::<RuleFile>("compose = [\"variant1\"]\nspare_bonus = 5").is_err());
+        assert!(toml::from_str::<Rules>("compose = [\"variant9\"]").unwrap().into_variant(&Registry::default()).is_err());
+    }
+}
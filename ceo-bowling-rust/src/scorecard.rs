@@ -0,0 +1,609 @@
+use std::convert::Infallible;
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, BufRead, Read};
+use std::path::Path;
+use std::str::FromStr;
+use anyhow::{bail, Error, Result};
+use itertools::Itertools;
+use serde::Deserialize;
+use serde_json::Value;
+use crate::frame::{Frame, GameRules, TenthFrame, FRAMES_PER_GAME};
+
+/// A single roll on a scorecard line together with where it was written
+struct Roll<'a> {
+    column: usize,
+    token: &'a str,
+    pins: u8,
+}
+
+impl Roll<'_> {
+    fn error(&self, message: impl Into<String>) -> LineError {
+        LineError {
+            column: self.column,
+            token: self.token.to_owned(),
+            message: message.into(),
+        }
+    }
+}
+
+/// A problem with a roll or a frame on a single scorecard line
+#[derive(Clone, Debug, PartialEq)]
+pub struct LineError {
+    pub column: usize,
+    pub token: String,
+    pub message: String,
+}
+
+impl Display for LineError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "column {}: {} '{}'", self.column, self.message, self.token)
+    }
+}
+
+/// A problem with a scorecard, located by file, line and column
+#[derive(Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub file: String,
+    pub line: usize,
+    pub error: LineError,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}: {} '{}'", self.file, self.line, self.error.column, self.error.message, self.error.token)
+    }
+}
+
+/// Every problem found while parsing a scorecard
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScorecardError(pub Vec<Diagnostic>);
+
+impl Display for ScorecardError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.iter().join("\n"))
+    }
+}
+
+impl std::error::Error for ScorecardError {}
+
+/// One bowler's game as written on a single scorecard line
+#[derive(Clone, Debug, PartialEq)]
+pub struct Series {
+    pub name: String,
+    pub frames: Vec<Frame>,
+}
+
+/// All series read from one scorecard, named after the file it came from
+#[derive(Clone, Debug, PartialEq)]
+pub struct Scorecard {
+    pub name: String,
+    pub series: Vec<Series>,
+}
+
+impl Scorecard {
+    /// Parse every line of a scorecard, skipping blank lines and collecting all problems rather than just the first
+    pub fn parse(name: impl Into<String>, scorecard: &str, notation: Notation, rules: GameRules) -> Result<Scorecard, ScorecardError> {
+        let Ok(scorecard) = Scorecard::parse_lines(name.into(), scorecard.split('\n').map(|line| Ok::<_, Infallible>(line.to_owned())), notation, rules);
+        scorecard
+    }
+
+    /// Parse a scorecard in any of the input formats
+    pub fn read(name: impl Into<String>, scorecard: &str, format: InputFormat, notation: Notation, rules: GameRules) -> Result<Scorecard, ScorecardError> {
+        match format {
+            InputFormat::Text => Scorecard::parse(name, scorecard, notation, rules),
+            InputFormat::Csv => Scorecard::parse_csv(name, scorecard.as_bytes(), notation, rules),
+            InputFormat::Json => Scorecard::parse_json(name, scorecard.as_bytes(), notation, rules),
+        }
+    }
+
+    /// Parse a scorecard while reading it, a line at a time for text and a record at a time for CSV, failing
+    /// early only if reading fails
+    pub fn read_from(name: impl Into<String>, reader: impl BufRead, format: InputFormat, notation: Notation, rules: GameRules) -> io::Result<Result<Scorecard, ScorecardError>> {
+        match format {
+            InputFormat::Text => Scorecard::parse_lines(name.into(), reader.lines(), notation, rules),
+            InputFormat::Csv => Ok(Scorecard::parse_csv(name, reader, notation, rules)),
+            InputFormat::Json => Ok(Scorecard::parse_json(name, reader, notation, rules)),
+        }
+    }
+
+    fn parse_lines<E>(name: String, lines: impl Iterator<Item = Result<String, E>>, notation: Notation, rules: GameRules) -> Result<Result<Scorecard, ScorecardError>, E> {
+        let mut series = Vec::new();
+        let mut diagnostics = Vec::new();
+        for (index, line) in lines.enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            match parse_line(&line, notation, rules) {
+                Ok((bowler, frames)) => series.push(Series { name: bowler.to_owned(), frames }),
+                Err(errors) => diagnostics.extend(errors.into_iter()
+                    .map(|error| Diagnostic { file: name.clone(), line: index + 1, error })),
+            }
+        }
+        if diagnostics.is_empty() {
+            Ok(Ok(Scorecard { name, series }))
+        } else {
+            Ok(Err(ScorecardError(diagnostics)))
+        }
+    }
+
+    /// Parse CSV with a row per game of the form `name,game,roll1,roll2,...`, where the game column isn't
+    /// used for scoring and an optional header row starts with `name,game`. Empty trailing roll columns are
+    /// ignored and columns in diagnostics are field numbers.
+    pub fn parse_csv(name: impl Into<String>, scorecard: impl Read, notation: Notation, rules: GameRules) -> Result<Scorecard, ScorecardError> {
+        let name = name.into();
+        let mut series = Vec::new();
+        let mut diagnostics = Vec::new();
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .trim(csv::Trim::All)
+            .from_reader(scorecard);
+        for (index, record) in reader.records().enumerate() {
+            let diagnostic = |line, error| Diagnostic { file: name.clone(), line, error };
+            let record = match record {
+                Ok(record) => record,
+                Err(error) => {
+                    let line = error.position().map_or(index + 1, |position| position.line() as usize);
+                    diagnostics.push(diagnostic(line, LineError { column: 1, token: String::new(), message: error.to_string() }));
+                    continue;
+                }
+            };
+            let line = record.position().map_or(index + 1, |position| position.line() as usize);
+            let bowler = record.get(0).unwrap_or_default();
+            if index == 0 && bowler.eq_ignore_ascii_case("name") && record.get(1).is_some_and(|game| game.eq_ignore_ascii_case("game")) {
+                continue;
+            }
+            if record.iter().all(str::is_empty) {
+                continue;
+            }
+            if record.len() < 2 {
+                diagnostics.push(diagnostic(line, LineError { column: 1, token: bowler.to_owned(), message: "Missing game column".to_owned() }));
+                continue;
+            }
+            let rolls = record.iter().enumerate().skip(2).collect_vec();
+            let end = rolls.iter().rposition(|(_, token)| !token.is_empty()).map_or(0, |last| last + 1);
+            match parse_tokens(rolls[..end].iter().map(|(field, token)| (field + 1, *token)), notation, rules) {
+                Ok(frames) => series.push(Series { name: bowler.to_owned(), frames }),
+                Err(errors) => diagnostics.extend(errors.into_iter().map(|error| diagnostic(line, error))),
+            }
+        }
+        if diagnostics.is_empty() {
+            Ok(Scorecard { name, series })
+        } else {
+            Err(ScorecardError(diagnostics))
+        }
+    }
+
+    /// Parse a JSON array of games of the form `{"name": "Eve Stojbs", "game": 1, "rolls": [10, 7, 3]}`, where
+    /// the game isn't used for scoring and rolls can also be strings in the notation. Diagnostics are located
+    /// by game and roll number rather than line and column.
+    pub fn parse_json(name: impl Into<String>, scorecard: impl Read, notation: Notation, rules: GameRules) -> Result<Scorecard, ScorecardError> {
+        #[derive(Deserialize)]
+        struct Game {
+            name: String,
+            rolls: Vec<Value>,
+        }
+
+        let name = name.into();
+        let games: Vec<Game> = serde_json::from_reader(scorecard).map_err(|error| ScorecardError(vec![Diagnostic {
+            file: name.clone(),
+            line: error.line(),
+            error: LineError { column: error.column(), token: String::new(), message: error.to_string() },
+        }]))?;
+        let mut series = Vec::new();
+        let mut diagnostics = Vec::new();
+        for (index, game) in games.into_iter().enumerate() {
+            let tokens = game.rolls.iter()
+                .map(|roll| match roll {
+                    Value::String(token) => token.clone(),
+                    roll => roll.to_string(),
+                })
+                .collect_vec();
+            match parse_tokens(tokens.iter().enumerate().map(|(roll, token)| (roll + 1, token.as_str())), notation, rules) {
+                Ok(frames) => series.push(Series { name: game.name, frames }),
+                Err(errors) => diagnostics.extend(errors.into_iter()
+                    .map(|error| Diagnostic { file: name.clone(), line: index + 1, error })),
+            }
+        }
+        if diagnostics.is_empty() {
+            Ok(Scorecard { name, series })
+        } else {
+            Err(ScorecardError(diagnostics))
+        }
+    }
+}
+
+/// How a scorecard file is laid out
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InputFormat {
+    /// A line per game with the name followed by the rolls
+    #[default]
+    Text,
+    Csv,
+    Json,
+}
+
+impl FromStr for InputFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            _ if s.eq_ignore_ascii_case("text") => Ok(InputFormat::Text),
+            _ if s.eq_ignore_ascii_case("csv") => Ok(InputFormat::Csv),
+            _ if s.eq_ignore_ascii_case("json") => Ok(InputFormat::Json),
+            _ => bail!("Invalid input format {}", s),
+        }
+    }
+}
+
+impl InputFormat {
+    /// Pick the format from a `.csv` or `.json` extension, anything else is text
+    pub fn from_path(path: impl AsRef<Path>) -> InputFormat {
+        match path.as_ref().extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("csv") => InputFormat::Csv,
+            Some(extension) if extension.eq_ignore_ascii_case("json") => InputFormat::Json,
+            _ => InputFormat::Text,
+        }
+    }
+}
+
+/// Check every line of a scorecard, returning all problems found rather than just the first
+pub fn validate_scorecard(file: &str, scorecard: &str, notation: Notation, rules: GameRules) -> Vec<Diagnostic> {
+    Scorecard::parse(file, scorecard, notation, rules)
+        .err()
+        .map(|error| error.0)
+        .unwrap_or_default()
+}
+
+/// How the rolls on a scorecard line are written
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Notation {
+    /// Every roll as its pin count, e.g. `10 7 3 9 0`
+    #[default]
+    Numeric,
+    /// One token per frame using `X` for strikes, `/` for spares, `-` for misses, `F` for fouls and
+    /// `S` in front of a roll that left a split, e.g. `X 7/ 9- F8 S8/`
+    Traditional,
+}
+
+impl FromStr for Notation {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            _ if s.eq_ignore_ascii_case("numeric") => Ok(Notation::Numeric),
+            _ if s.eq_ignore_ascii_case("traditional") => Ok(Notation::Traditional),
+            _ => bail!("Invalid notation {}", s),
+        }
+    }
+}
+
+impl Notation {
+    /// Byte offset where the rolls start, everything before it is the name
+    fn rolls_start(&self, line: &str) -> Option<usize> {
+        match self {
+            Notation::Numeric => line.find(char::is_numeric),
+            Notation::Traditional => tokenize(line)
+                .find(|(_, token)| token.trim_start_matches(['F', 'S']).starts_with(|c: char| c.is_ascii_digit() || c == 'X' || c == '-')
+                    && token.chars().all(|c| c.is_ascii_digit() || "X/-FS".contains(c)))
+                .map(|(offset, _)| offset),
+        }
+    }
+
+    fn parse_token<'a>(&self, column: usize, token: &'a str, rules: GameRules) -> Result<Vec<Roll<'a>>, LineError> {
+        let roll = Roll { column, token, pins: 0 };
+        match self {
+            Notation::Numeric => match u32::from_str(token) {
+                Ok(pins) if pins <= 10 => Ok(vec![Roll { pins: pins as u8, ..roll }]),
+                Ok(_) => Err(roll.error("Pin count over 10")),
+                Err(_) => Err(roll.error("Not a pin count")),
+            },
+            Notation::Traditional => {
+                let mut rolls: Vec<Roll> = Vec::new();
+                for (index, (offset, symbol)) in token.char_indices().enumerate() {
+                    let roll = Roll { column: column + index, token: &token[offset..offset + symbol.len_utf8()], pins: 0 };
+                    let pins = match symbol {
+                        'S' => continue,
+                        'X' => 10,
+                        '-' | 'F' => 0,
+                        '/' => match rolls.last() {
+                            Some(previous) if !rules.is_strike(previous.pins) => 10 - previous.pins,
+                            _ => return Err(roll.error("Spare without a first roll")),
+                        },
+                        _ => match symbol.to_digit(10) {
+                            Some(pins) => pins as u8,
+                            None => return Err(roll.error("Not a roll")),
+                        },
+                    };
+                    rolls.push(Roll { pins, ..roll });
+                }
+                Ok(rolls)
+            }
+        }
+    }
+}
+
+/// Parse a scorecard line into the bowler's name and frames.
+///
+/// The name can be quoted, as in `"Player 2" 3 5`, or ended with a colon, as in `R2D2: 3 5`, so that it can contain
+/// digits. Otherwise it is everything before the first roll.
+pub fn parse_line(line: &str, notation: Notation, rules: GameRules) -> Result<(&str, Vec<Frame>), Vec<LineError>> {
+    let (name, score_start) = split_name(line, notation).map_err(|error| vec![error])?;
+    let scores = &line[score_start..];
+    let tokens = tokenize(scores).map(|(offset, token)| (column(line, score_start + offset), token));
+    Ok((name, parse_tokens(tokens, notation, rules)?))
+}
+
+/// The name on a line and the byte offset where the rolls start
+fn split_name(line: &str, notation: Notation) -> Result<(&str, usize), LineError> {
+    let trimmed = line.trim_start();
+    if let Some(quoted) = trimmed.strip_prefix('"') {
+        let quote = line.len() - trimmed.len();
+        let Some(length) = quoted.find('"') else {
+            return Err(LineError {
+                column: column(line, quote),
+                token: trimmed.to_owned(),
+                message: "Unterminated quoted name".to_owned(),
+            });
+        };
+        let name_end = quote + 1 + length;
+        let rest = &line[name_end + 1..];
+        let separator = rest.len() - rest.trim_start().trim_start_matches(':').len();
+        return Ok((&line[quote + 1..name_end], name_end + 1 + separator));
+    }
+    if let Some((name, _)) = line.split_once(':') {
+        return Ok((name.trim(), name.len() + 1));
+    }
+    match notation.rolls_start(line) {
+        Some(score_start) => Ok((line[..score_start].trim(), score_start)),
+        None => Ok((line.trim(), line.len())),
+    }
+}
+
+/// Parse the rolls of a series, each token with the column it was found in
+fn parse_tokens<'a>(tokens: impl Iterator<Item = (usize, &'a str)>, notation: Notation, rules: GameRules) -> Result<Vec<Frame>, Vec<LineError>> {
+    let (rolls, errors): (Vec<_>, Vec<_>) = tokens
+        .map(|(column, token)| notation.parse_token(column, token, rules))
+        .partition_result();
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    parse_series(&rolls.into_iter().flatten().collect_vec(), rules)
+}
+
+/// The pins of every roll written on a line, which needn't make up whole frames
+pub fn parse_rolls(line: &str, notation: Notation, rules: GameRules) -> Result<Vec<u8>, Vec<LineError>> {
+    let (rolls, errors): (Vec<_>, Vec<_>) = tokenize(line)
+        .map(|(offset, token)| notation.parse_token(column(line, offset), token, rules))
+        .partition_result();
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    Ok(rolls.into_iter().flatten().map(|roll| roll.pins).collect())
+}
+
+/// Split a line into whitespace separated tokens, each with its byte offset
+fn tokenize(line: &str) -> impl Iterator<Item = (usize, &str)> {
+    line.split_whitespace()
+        .map(move |token| (token.as_ptr() as usize - line.as_ptr() as usize, token))
+}
+
+/// The 1-based column of a byte offset in a line
+fn column(line: &str, offset: usize) -> usize {
+    line[..offset].chars().count() + 1
+}
+
+fn parse_series(rolls: &[Roll], rules: GameRules) -> Result<Vec<Frame>, Vec<LineError>> {
+    let mut series = Vec::new();
+    let mut errors = Vec::new();
+    let mut rolls = rolls.iter();
+    while let Some(first) = rolls.next() {
+        if series.len() == FRAMES_PER_GAME {
+            errors.push(first.error(format!("More than {} frames", FRAMES_PER_GAME)));
+            break;
+        }
+        let tenth = series.len() == FRAMES_PER_GAME - 1;
+        let strike = rules.is_strike(first.pins);
+        if strike && !tenth {
+            series.push(Frame::Strike);
+            continue;
+        }
+        let Some(second) = rolls.next() else {
+            errors.push(first.error(if tenth && strike {
+                "Missing fill balls in tenth frame"
+            } else {
+                "Missing second roll of frame"
+            }));
+            break;
+        };
+        errors.extend(check_frame_total(first, second, rules));
+        let spare = rules.is_spare(first.pins, second.pins);
+        let frame = if !tenth {
+            if spare {
+                Frame::Spare(first.pins)
+            } else {
+                Frame::Regular(first.pins, second.pins)
+            }
+        } else if strike || spare {
+            let Some(fill) = rolls.next() else {
+                errors.push(second.error("Missing fill ball in tenth frame"));
+                break;
+            };
+            if strike {
+                errors.extend(check_frame_total(second, fill, rules));
+                let (first_fill, second_fill) = rules.strike_fills(second.pins, fill.pins);
+                Frame::Tenth(TenthFrame::Strike(first_fill, second_fill))
+            } else {
+                Frame::Tenth(TenthFrame::Spare(first.pins, rules.fresh_rack(fill.pins)))
+            }
+        } else {
+            Frame::Tenth(TenthFrame::Regular(first.pins, second.pins))
+        };
+        series.push(frame);
+    }
+    if errors.is_empty() {
+        Ok(series)
+    } else {
+        Err(errors)
+    }
+}
+
+/// Two rolls from the same rack can't knock down more than ten pins
+fn check_frame_total(first: &Roll, second: &Roll, rules: GameRules) -> Option<LineError> {
+    let total = first.pins + second.pins;
+    (!rules.is_strike(first.pins) && total > 10).then(|| second.error(format!("Frame total of {} is over 10", total)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::frame::{Frame, GameRules, TenthFrame};
+    use super::{parse_line, validate_scorecard, InputFormat, Notation, Scorecard, Series};
+
+    #[test]
+    fn test_parse_scorecard() {
+        // Given a scorecard with a blank line
+        let input = "Yattas Del Lana 3 5 10 7 3\n\nEve Stojbs 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 9 1 10\n";
+
+        // When it is parsed
+        let scorecard = Scorecard::parse("league.txt", input, Notation::Numeric, GameRules::default()).unwrap();
+
+        // Then each bowler gets their own series
+        assert_eq!(scorecard, Scorecard {
+            name: "league.txt".to_owned(),
+            series: vec![
+                Series {
+                    name: "Yattas Del Lana".to_owned(),
+                    frames: vec![Frame::Regular(3, 5), Frame::Strike, Frame::Spare(7)],
+                },
+                Series {
+                    name: "Eve Stojbs".to_owned(),
+                    frames: [Frame::Regular(0, 0); 9].into_iter()
+                        .chain([Frame::Tenth(TenthFrame::Spare(9, 10))])
+                        .collect(),
+                },
+            ],
+        });
+    }
+
+    #[test]
+    fn test_validate_scorecard() {
+        // Given a scorecard with several problems
+        let input = "\
+            Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3\n\
+            Eve Stojbs 3 7 3 300 9 a\n\
+            Perfect Game 10 10 10 10 10 10 10 10 10 10 10\n\
+            Too Many 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1 1\n\
+            Bad Fill 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 10 7 5\n\
+            ";
+
+        // When it is validated
+        let diagnostics = validate_scorecard("league.txt", input, Notation::Numeric, GameRules::default());
+
+        // Then every problem is reported with its location
+        assert_eq!(diagnostics.iter().map(ToString::to_string).collect::<Vec<_>>(), [
+            "league.txt:2:18: Pin count over 10 '300'",
+            "league.txt:2:24: Not a pin count 'a'",
+            "league.txt:3:44: Missing fill ball in tenth frame '10'",
+            "league.txt:4:50: More than 10 frames '1'",
+            "league.txt:5:51: Frame total of 12 is over 10 '5'",
+        ]);
+    }
+
+    #[test]
+    fn test_parse_csv_and_json() {
+        // Given the same games as text, CSV and JSON
+        let text = "Yattas Del Lana 3 5 10 7 3\nEve Stojbs 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 9 1 10\n";
+        let csv = "\
+            name,game,roll1,roll2,roll3,roll4,roll5\n\
+            Yattas Del Lana,1,3,5,10,7,3\n\
+            \"Stojbs, Eve\",1,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,0,9,1,10\n\
+            ";
+        let json = r#"[
+            {"name": "Yattas Del Lana", "game": 1, "rolls": [3, 5, 10, 7, 3]},
+            {"name": "Stojbs, Eve", "game": 1, "rolls": [0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 9, 1, 10]}
+        ]"#;
+
+        // When they are read
+        let text = Scorecard::read("league.txt", text, InputFormat::Text, Notation::Numeric, GameRules::default()).unwrap();
+        let csv = Scorecard::read("league.csv", csv, InputFormat::Csv, Notation::Numeric, GameRules::default()).unwrap();
+        let json = Scorecard::read("league.json", json, InputFormat::Json, Notation::Numeric, GameRules::default()).unwrap();
+
+        // Then they give the same frames
+        for scorecard in [&csv, &json] {
+            assert_eq!(scorecard.series[0], text.series[0]);
+            assert_eq!(scorecard.series[1].name, "Stojbs, Eve");
+            assert_eq!(scorecard.series[1].frames, text.series[1].frames);
+        }
+    }
+
+    #[test]
+    fn test_invalid_csv_and_json() {
+        for (file, input, expected_diagnostics) in [
+            ("league.csv", "Eve Stojbs,1,3,11,,\nBo Ling\nYattas Del Lana,2,X,7,3", vec![
+                "league.csv:1:4: Pin count over 10 '11'",
+                "league.csv:2:1: Missing game column 'Bo Ling'",
+                "league.csv:3:3: Not a pin count 'X'",
+            ]),
+            ("league.json", r#"[{"name": "Eve Stojbs", "rolls": [3, 11]}, {"name": "Bo Ling", "rolls": [7, "3", 4]}]"#, vec![
+                "league.json:1:2: Pin count over 10 '11'",
+                "league.json:2:3: Missing second roll of frame '4'",
+            ]),
+        ] {
+            let diagnostics = Scorecard::read(file, input, InputFormat::from_path(file), Notation::Numeric, GameRules::default()).unwrap_err().0;
+            assert_eq!(diagnostics.iter().map(ToString::to_string).collect::<Vec<_>>(), expected_diagnostics);
+        }
+        assert!(Scorecard::parse_json("league.json", "{\"name\": \"Eve Stojbs\"}".as_bytes(), Notation::Numeric, GameRules::default()).is_err());
+    }
+
+    #[test]
+    fn test_parse_names_with_digits() {
+        for (line, expected_name) in [
+            ("\"Player 2\" 3 5 10", "Player 2"),
+            ("  \"R2D2\": 3 5 10", "R2D2"),
+            ("R2D2: 3 5 10", "R2D2"),
+            ("Player 2 : 3 5 10", "Player 2"),
+            ("Eve Stojbs 3 5 10", "Eve Stojbs"),
+        ] {
+            // Given a name that is quoted, ends with a colon or has no digits
+            let (name, frames) = parse_line(line, Notation::Numeric, GameRules::default()).unwrap();
+
+            // Expect the name to be kept whole and only the rolls to be scored
+            assert_eq!(name, expected_name, "{}", line);
+            assert_eq!(frames, [Frame::Regular(3, 5), Frame::Strike], "{}", line);
+        }
+        let (name, frames) = parse_line("\"Player 2\" X 7/", Notation::Traditional, GameRules::default()).unwrap();
+        assert_eq!((name, frames), ("Player 2", vec![Frame::Strike, Frame::Spare(7)]));
+
+        let errors = parse_line("\"Player 2 3 5", Notation::Numeric, GameRules::default()).unwrap_err();
+        assert_eq!(errors[0].to_string(), "column 1: Unterminated quoted name '\"Player 2 3 5'");
+        let errors = parse_line("\"Player 2\" 3 x", Notation::Numeric, GameRules::default()).unwrap_err();
+        assert_eq!(errors[0].to_string(), "column 14: Not a pin count 'x'");
+    }
+
+    #[test]
+    fn test_parse_no_tap() {
+        // Given no-tap rules where nine pins on the first ball are a strike
+        let rules = GameRules::no_tap(9);
+
+        // Expect nine to be a strike in either notation, and a spare only after fewer
+        let (_, frames) = parse_line("Eve Stojbs 8/ 9 7-", Notation::Traditional, rules).unwrap();
+        assert_eq!(frames, [Frame::Spare(8), Frame::Strike, Frame::Regular(7, 0)]);
+        assert_eq!(parse_line("Eve Stojbs 8 2 9 7 0", Notation::Numeric, rules).unwrap().1, frames);
+
+        // And a spare after a strike to be reported where it is written
+        let errors = parse_line("Eve 9/ 7-", Notation::Traditional, rules).unwrap_err();
+        assert_eq!(errors[0].to_string(), "column 6: Spare without a first roll '/'");
+    }
+
+    #[test]
+    fn test_read_from_stream() {
+        // Given a scorecard that is read while streaming
+        let input = "Yattas Del Lana 3 5 10 7 3\r\n\r\nEve Stojbs 3 x\n";
+        let scorecard = Scorecard::read_from("<stdin>", input.as_bytes(), InputFormat::Text, Notation::Numeric, GameRules::default()).unwrap();
+
+        // Expect the same result as when parsing it whole
+        assert_eq!(scorecard, Scorecard::parse("<stdin>", input, Notation::Numeric, GameRules::default()));
+        assert_eq!(scorecard.unwrap_err().to_string(), "<stdin>:3:14: Not a pin count 'x'");
+    }
+}
@@ -0,0 +1,184 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use serde::Serialize;
+use crate::scorecard::{Scorecard, Series};
+use crate::tiebreak::TieBreaker;
+use crate::variants::ScoreCalculator;
+
+/// A bowler's place in the standings across all scorecards
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Standing {
+    /// Bowlers that are level after all tie-break rules share a rank, and the next rank is skipped
+    pub rank: usize,
+    pub name: String,
+    pub total: u32,
+    pub games: usize,
+    pub subtotals: Vec<Subtotal>,
+}
+
+/// A bowler's total on a single scorecard
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Subtotal {
+    pub scorecard: String,
+    pub total: u32,
+    pub games: usize,
+}
+
+/// Every bowler on the scorecards, highest total first, with ties broken by the rules in the order given
+pub fn standings(scorecards: &[Scorecard], variant: &dyn ScoreCalculator, tie_breakers: &[&dyn TieBreaker]) -> Vec<Standing> {
+    standings_by(scorecards, &|series| variant.calculate_score(&series.frames), variant, tie_breakers)
+}
+
+/// Standings where every game counts for the score given, e.g. including a handicap, while the tie-break rules
+/// still rate games by the variant
+pub fn standings_by(scorecards: &[Scorecard], score: &dyn Fn(&Series) -> u32, variant: &dyn ScoreCalculator, tie_breakers: &[&dyn TieBreaker]) -> Vec<Standing> {
+    let mut bowlers: BTreeMap<&str, (Standing, Vec<&Series>)> = BTreeMap::new();
+    for scorecard in scorecards {
+        for series in &scorecard.series {
+            let score = score(series);
+            let (standing, games) = bowlers.entry(&series.name).or_insert_with(|| (Standing {
+                rank: 0,
+                name: series.name.clone(),
+                total: 0,
+                games: 0,
+                subtotals: Vec::new(),
+            }, Vec::new()));
+            standing.total += score;
+            standing.games += 1;
+            games.push(series);
+            match standing.subtotals.last_mut() {
+                Some(subtotal) if subtotal.scorecard == scorecard.name => {
+                    subtotal.total += score;
+                    subtotal.games += 1;
+                }
+                _ => standing.subtotals.push(Subtotal {
+                    scorecard: scorecard.name.clone(),
+                    total: score,
+                    games: 1,
+                }),
+            }
+        }
+    }
+    let mut standings: Vec<_> = bowlers.into_values().collect();
+    standings.sort_by_key(|(standing, _)| Reverse(standing.total));
+    let mut rank = 1;
+    for level in standings.chunk_by_mut(|(a, _), (b, _)| a.total == b.total) {
+        let mut start = 0;
+        for size in break_ties(level, variant, tie_breakers) {
+            for (standing, _) in &mut level[start..start + size] {
+                standing.rank = rank;
+            }
+            start += size;
+            rank += size;
+        }
+    }
+    standings.into_iter().map(|(standing, _)| standing).collect()
+}
+
+/// Rank entries that are sorted best first, those that are level sharing a rank and the next rank being skipped
+pub fn rank<T>(sorted: &mut [T], level: impl FnMut(&T, &T) -> bool, rank_of: impl Fn(&mut T) -> &mut usize) {
+    let mut rank = 1;
+    for level in sorted.chunk_by_mut(level) {
+        let size = level.len();
+        for entry in level {
+            *rank_of(entry) = rank;
+        }
+        rank += size;
+    }
+}
+
+/// Order bowlers that are level by the first rule that can rate all of them, then the remaining rules for
+/// those that are still level, returning the sizes of the groups that remain tied
+fn break_ties(level: &mut [(Standing, Vec<&Series>)], variant: &dyn ScoreCalculator, tie_breakers: &[&dyn TieBreaker]) -> Vec<usize> {
+    let Some((tie_breaker, tie_breakers)) = tie_breakers.split_first().filter(|_| level.len() > 1) else {
+        return vec![level.len()];
+    };
+    let rates: Vec<_> = level.iter()
+        .map(|(standing, games)| tie_breaker.rate(&standing.name, games, variant))
+        .collect();
+    if rates.iter().any(Option::is_none) {
+        return break_ties(level, variant, tie_breakers);
+    }
+    let mut rated: Vec<_> = rates.into_iter().zip(level.iter().cloned()).collect();
+    rated.sort_by_key(|(rate, _)| Reverse(*rate));
+    let rates: Vec<_> = rated.iter().map(|(rate, _)| *rate).collect();
+    for (bowler, (_, rated)) in level.iter_mut().zip(rated) {
+        *bowler = rated;
+    }
+    let mut start = 0;
+    let mut sizes = Vec::new();
+    for tied in rates.chunk_by(|a, b| a == b) {
+        sizes.extend(break_ties(&mut level[start..start + tied.len()], variant, tie_breakers));
+        start += tied.len();
+    }
+    sizes
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::frame::GameRules;
+    use crate::scorecard::{Notation, Scorecard};
+    use crate::variants::Variant1;
+    use super::{rank, standings, Standing, Subtotal};
+
+    #[test]
+    fn test_standings() {
+        // Given two scorecards
+        let scorecards = [
+            Scorecard::parse("monday.txt", "\
+                Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3\n\
+                Eve Stojbs 3 7 3 3 9 1 6 4 2 3 1 0\n\
+                Eve Stojbs 1 1\n\
+                ", Notation::Numeric, GameRules::default()).unwrap(),
+            Scorecard::parse("tuesday.txt", "\
+                Bo Ling 9 0 9 0 9 0 9 0 9 0\n\
+                Yattas Del Lana 1 1\n\
+                ", Notation::Numeric, GameRules::default()).unwrap(),
+        ];
+
+        // When the standings are calculated
+        let standings = standings(&scorecards, &Variant1, &[]);
+
+        // Then everyone is ranked by their total, sharing the rank on ties
+        let subtotal = |scorecard: &str, total, games| Subtotal { scorecard: scorecard.to_owned(), total, games };
+        assert_eq!(standings, [
+            Standing {
+                rank: 1,
+                name: "Yattas Del Lana".to_owned(),
+                total: 47,
+                games: 2,
+                subtotals: vec![subtotal("monday.txt", 45, 1), subtotal("tuesday.txt", 2, 1)],
+            },
+            Standing {
+                rank: 2,
+                name: "Bo Ling".to_owned(),
+                total: 45,
+                games: 1,
+                subtotals: vec![subtotal("tuesday.txt", 45, 1)],
+            },
+            Standing {
+                rank: 3,
+                name: "Eve Stojbs".to_owned(),
+                total: 44,
+                games: 2,
+                subtotals: vec![subtotal("monday.txt", 44, 2)],
+            },
+        ]);
+    }
+
+    #[test]
+    fn test_standings_shared_rank() {
+        let scorecards = [Scorecard::parse("monday.txt", "B 5 4\nA 9 0\nC 1 1\n", Notation::Numeric, GameRules::default()).unwrap()];
+        let ranks: Vec<_> = standings(&scorecards, &Variant1, &[]).into_iter()
+            .map(|standing| (standing.rank, standing.name))
+            .collect();
+        assert_eq!(ranks, [(1, "A".to_owned()), (1, "B".to_owned()), (3, "C".to_owned())]);
+    }
+
+    #[test]
+    fn test_rank() {
+        let mut entries = [(9, 0), (7, 0), (7, 0), (7, 0), (2, 0)];
+        rank(&mut entries, |a, b| a.0 == b.0, |entry| &mut entry.1);
+        assert_eq!(entries.map(|(_, rank)| rank), [1, 2, 2, 2, 5]);
+    }
+}
@@ -0,0 +1,139 @@
+use std::collections::BTreeMap;
+use crate::frame::{Frame, TenthFrame};
+use crate::scorecard::Scorecard;
+use crate::variants::ScoreCalculator;
+
+/// A bowler's counts over all their games, with scores by the variant and everything else by frame
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub name: String,
+    pub games: usize,
+    pub total: u32,
+    pub high_game: u32,
+    /// The highest total of the games on one scorecard
+    pub high_series: u32,
+    pub frames: usize,
+    /// Frames where the first ball was a strike, fill balls aren't counted
+    pub strikes: usize,
+    pub spares: usize,
+    /// Frames where the first ball left pins standing
+    pub spare_chances: usize,
+    pub single_pin_spares: usize,
+    /// Frames where the first ball left a single pin standing
+    pub single_pin_chances: usize,
+    pub open_frames: usize,
+    pub first_ball_pins: u32,
+}
+
+impl Stats {
+    pub fn average(&self) -> f64 {
+        ratio(self.total as usize, self.games)
+    }
+
+    pub fn strike_percentage(&self) -> f64 {
+        100.0 * ratio(self.strikes, self.frames)
+    }
+
+    pub fn spare_percentage(&self) -> f64 {
+        100.0 * ratio(self.spares, self.spare_chances)
+    }
+
+    pub fn single_pin_conversion(&self) -> f64 {
+        100.0 * ratio(self.single_pin_spares, self.single_pin_chances)
+    }
+
+    pub fn first_ball_average(&self) -> f64 {
+        ratio(self.first_ball_pins as usize, self.frames)
+    }
+
+    fn add_frame(&mut self, frame: &Frame) {
+        let first_ball = frame.rolls()[0];
+        let spare = matches!(frame, Frame::Spare(_) | Frame::Tenth(TenthFrame::Spare(_, _)));
+        self.frames += 1;
+        self.first_ball_pins += first_ball as u32;
+        if first_ball == 10 {
+            self.strikes += 1;
+            return;
+        }
+        self.spare_chances += 1;
+        self.spares += spare as usize;
+        self.open_frames += frame.is_open() as usize;
+        if first_ball == 9 {
+            self.single_pin_chances += 1;
+            self.single_pin_spares += spare as usize;
+        }
+    }
+}
+
+/// Zero rather than NaN when there is nothing to divide by
+fn ratio(count: usize, of: usize) -> f64 {
+    if of == 0 { 0.0 } else { count as f64 / of as f64 }
+}
+
+/// The stats of every bowler on the scorecards, in name order
+pub fn stats(scorecards: &[Scorecard], variant: &dyn ScoreCalculator) -> Vec<Stats> {
+    let mut bowlers: BTreeMap<&str, Stats> = BTreeMap::new();
+    for scorecard in scorecards {
+        let mut series_totals: BTreeMap<&str, u32> = BTreeMap::new();
+        for series in &scorecard.series {
+            let score = variant.calculate_score(&series.frames);
+            let stats = bowlers.entry(&series.name).or_insert_with(|| Stats { name: series.name.clone(), ..Stats::default() });
+            stats.games += 1;
+            stats.total += score;
+            stats.high_game = stats.high_game.max(score);
+            series.frames.iter().for_each(|frame| stats.add_frame(frame));
+            *series_totals.entry(&series.name).or_default() += score;
+        }
+        for (name, series_total) in series_totals {
+            let stats = bowlers.get_mut(name).unwrap();
+            stats.high_series = stats.high_series.max(series_total);
+        }
+    }
+    bowlers.into_values().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::frame::GameRules;
+    use crate::scorecard::{Notation, Scorecard};
+    use crate::variants::Variant4;
+    use super::{stats, Stats};
+
+    #[test]
+    fn test_stats() {
+        // Given two nights of games
+        let scorecards = [
+            Scorecard::parse("monday.txt", "\
+                Eve Stojbs X 9/ 9- 81 X X 7/ 9/ -- XX9\n\
+                Eve Stojbs 9- 9- 9- 9- 9- 9- 9- 9- 9- 9-\n\
+                ", Notation::Traditional, GameRules::default()).unwrap(),
+            Scorecard::parse("tuesday.txt", "Eve Stojbs X X X X X X X X X XXX\n", Notation::Traditional, GameRules::default()).unwrap(),
+        ];
+
+        // When the stats are calculated
+        let stats = stats(&scorecards, &Variant4::default());
+
+        // Then every game and frame is counted
+        assert_eq!(stats, [Stats {
+            name: "Eve Stojbs".to_owned(),
+            games: 3,
+            total: 162 + 90 + 300,
+            high_game: 300,
+            high_series: 300,
+            frames: 30,
+            strikes: 14,
+            spares: 3,
+            spare_chances: 16,
+            single_pin_spares: 2,
+            single_pin_chances: 13,
+            open_frames: 13,
+            first_ball_pins: 10 * 14 + 9 * 13 + 8 + 7,
+        }]);
+        let stats = &stats[0];
+        assert_eq!(stats.average(), 552.0 / 3.0);
+        assert_eq!(stats.strike_percentage(), 100.0 * 14.0 / 30.0);
+        assert_eq!(stats.spare_percentage(), 100.0 * 3.0 / 16.0);
+        assert_eq!(stats.single_pin_conversion(), 100.0 * 2.0 / 13.0);
+        assert_eq!(stats.first_ball_average(), 272.0 / 30.0);
+    }
+}
@@ -0,0 +1,255 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::path::Path;
+use anyhow::{bail, Result};
+use serde::Deserialize;
+use crate::config::load_config;
+use crate::scorecard::Scorecard;
+use crate::standings::rank;
+use crate::variants::ScoreCalculator;
+
+/// The teams of a league, read from a TOML or JSON roster file
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Roster {
+    /// Score for every bowler missing from a team game, unless the team has its own
+    #[serde(default)]
+    pub blind: u32,
+    pub teams: Vec<Team>,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Team {
+    pub name: String,
+    /// The regular bowlers, as many as bowl for the team every game
+    pub bowlers: Vec<String>,
+    /// Bowlers who fill in for absent regulars
+    #[serde(default)]
+    pub substitutes: Vec<String>,
+    pub blind: Option<u32>,
+}
+
+/// One game of a team on one scorecard, the first game of every regular being game 1 and so on, with substitutes
+/// filling in for the regulars missing from a game
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TeamGame {
+    pub team: String,
+    pub scorecard: String,
+    pub game: usize,
+    /// The bowlers who bowled the game and their scores, regulars and substitutes alike
+    pub scores: Vec<(String, u32)>,
+    /// Absent bowlers that weren't replaced by a substitute
+    pub blinds: usize,
+    pub total: u32,
+}
+
+/// A team's place in the standings
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TeamStanding {
+    /// Teams with the same total share a rank, and the next rank is skipped
+    pub rank: usize,
+    pub name: String,
+    pub total: u32,
+    pub games: usize,
+    pub blinds: usize,
+    /// The team's total on every scorecard it bowled on, i.e. its series
+    pub series: Vec<(String, u32)>,
+}
+
+impl Roster {
+    /// Read a roster file, checking that every bowler is on a single team
+    pub fn load(path: impl AsRef<Path>) -> Result<Roster> {
+        let roster: Roster = load_config("roster", path)?;
+        roster.validate()?;
+        Ok(roster)
+    }
+
+    /// Every bowler, regular or substitute, must be on a single team
+    pub fn validate(&self) -> Result<()> {
+        let mut teams: BTreeMap<&str, &str> = BTreeMap::new();
+        for team in &self.teams {
+            for bowler in team.bowlers.iter().chain(&team.substitutes) {
+                if let Some(other) = teams.insert(bowler, &team.name) {
+                    bail!("{} is on both {} and {}", bowler, other, team.name);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn team_of(&self, bowler: &str) -> Option<&Team> {
+        self.team_index(bowler).map(|index| &self.teams[index])
+    }
+
+    fn team_index(&self, bowler: &str) -> Option<usize> {
+        self.teams.iter().position(|team| team.bowlers.iter().chain(&team.substitutes).any(|name| name == bowler))
+    }
+}
+
+/// Every game of every team that bowled on the scorecards, filling in blind scores for missing bowlers. Bowlers
+/// that aren't on the roster are left out.
+pub fn team_games(scorecards: &[Scorecard], roster: &Roster, variant: &dyn ScoreCalculator) -> Vec<TeamGame> {
+    let mut games = Vec::new();
+    for scorecard in scorecards {
+        for team in &roster.teams {
+            let mut team_games: Vec<Vec<(String, u32)>> = Vec::new();
+            let mut regular_games: BTreeMap<&str, usize> = BTreeMap::new();
+            for series in scorecard.series.iter().filter(|series| team.bowlers.contains(&series.name)) {
+                let game = regular_games.entry(&series.name).or_default();
+                if *game == team_games.len() {
+                    team_games.push(Vec::new());
+                }
+                team_games[*game].push((series.name.clone(), variant.calculate_score(&series.frames)));
+                *game += 1;
+            }
+            // Substitutes fill in for the regulars missing from the earliest games they haven't bowled in yet
+            for series in scorecard.series.iter().filter(|series| team.substitutes.contains(&series.name)) {
+                let game = team_games.iter()
+                    .position(|scores| scores.len() < team.bowlers.len() && scores.iter().all(|(name, _)| *name != series.name))
+                    .unwrap_or_else(|| {
+                        team_games.push(Vec::new());
+                        team_games.len() - 1
+                    });
+                team_games[game].push((series.name.clone(), variant.calculate_score(&series.frames)));
+            }
+            for (game, scores) in team_games.into_iter().enumerate() {
+                let blinds = team.bowlers.len().saturating_sub(scores.len());
+                let blind = team.blind.unwrap_or(roster.blind);
+                games.push(TeamGame {
+                    team: team.name.clone(),
+                    scorecard: scorecard.name.clone(),
+                    game: game + 1,
+                    total: scores.iter().map(|(_, score)| score).sum::<u32>() + blind * blinds as u32,
+                    scores,
+                    blinds,
+                });
+            }
+        }
+    }
+    games
+}
+
+/// Every team that bowled on the scorecards, highest total first
+pub fn team_standings(scorecards: &[Scorecard], roster: &Roster, variant: &dyn ScoreCalculator) -> Vec<TeamStanding> {
+    let mut standings: Vec<TeamStanding> = Vec::new();
+    for game in team_games(scorecards, roster, variant) {
+        let standing = match standings.iter().position(|standing| standing.name == game.team) {
+            Some(index) => &mut standings[index],
+            None => {
+                standings.push(TeamStanding {
+                    rank: 0,
+                    name: game.team.clone(),
+                    total: 0,
+                    games: 0,
+                    blinds: 0,
+                    series: Vec::new(),
+                });
+                standings.last_mut().unwrap()
+            }
+        };
+        standing.total += game.total;
+        standing.games += 1;
+        standing.blinds += game.blinds;
+        match standing.series.last_mut() {
+            Some((scorecard, total)) if *scorecard == game.scorecard => *total += game.total,
+            _ => standing.series.push((game.scorecard, game.total)),
+        }
+    }
+    standings.sort_by_key(|standing| Reverse(standing.total));
+    rank(&mut standings, |a, b| a.total == b.total, |standing| &mut standing.rank);
+    standings
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::frame::GameRules;
+    use crate::scorecard::{Notation, Scorecard};
+    use crate::variants::Variant1;
+    use super::{team_games, team_standings, Roster};
+
+    fn roster() -> Roster {
+        toml::from_str("\
+            blind = 50\n\
+            [[teams]]\n\
+            name = \"Pin Pals\"\n\
+            bowlers = [\"Eve Stojbs\", \"Bo Ling\"]\n\
+            substitutes = [\"Sam Sub\"]\n\
+            [[teams]]\n\
+            name = \"Gutter Gang\"\n\
+            bowlers = [\"Yattas Del Lana\", \"Ally Lane\"]\n\
+            blind = 40\n\
+            ").unwrap()
+    }
+
+    #[test]
+    fn test_team_games() {
+        // Given a night where a regular is replaced by a substitute and another is absent
+        let scorecards = [Scorecard::parse("monday.txt", "\
+            Eve Stojbs 9 0 9 0\n\
+            Sam Sub 5 0\n\
+            Yattas Del Lana 3 3\n\
+            Eve Stojbs 1 1\n\
+            Stranger 9 0\n\
+            ", Notation::Numeric, GameRules::default()).unwrap()];
+
+        // When the team games are totalled
+        let games = team_games(&scorecards, &roster(), &Variant1);
+
+        // Then substitutes count for their team and missing bowlers get the blind score
+        let totals = games.iter()
+            .map(|game| (game.team.as_str(), game.game, game.scores.len(), game.blinds, game.total))
+            .collect::<Vec<_>>();
+        assert_eq!(totals, [("Pin Pals", 1, 2, 0, 23), ("Pin Pals", 2, 1, 1, 52), ("Gutter Gang", 1, 1, 1, 46)]);
+    }
+
+    #[test]
+    fn test_team_games_substitute_mid_series() {
+        // Given a regular who leaves after two games and a substitute who bowls the third
+        let scorecards = [Scorecard::parse("monday.txt", "\
+            Eve Stojbs 9 0\n\
+            Bo Ling 4 0\n\
+            Eve Stojbs 9 0\n\
+            Bo Ling 4 0\n\
+            Eve Stojbs 9 0\n\
+            Sam Sub 4 0\n\
+            ", Notation::Numeric, GameRules::default()).unwrap()];
+
+        // When the team games are totalled
+        let games = team_games(&scorecards, &roster(), &Variant1);
+
+        // Then the substitute fills the game the regular missed, and no game has more scores than regulars
+        let totals = games.iter()
+            .map(|game| (game.game, game.scores.len(), game.blinds, game.total))
+            .collect::<Vec<_>>();
+        assert_eq!(totals, [(1, 2, 0, 13), (2, 2, 0, 13), (3, 2, 0, 13)]);
+    }
+
+    #[test]
+    fn test_team_standings() {
+        let scorecards = [
+            Scorecard::parse("monday.txt", "Eve Stojbs 9 0\nBo Ling 1 0\nYattas Del Lana 3 3\nAlly Lane 4 0\n", Notation::Numeric, GameRules::default()).unwrap(),
+            Scorecard::parse("tuesday.txt", "Bo Ling 1 0\nYattas Del Lana 3 3\nAlly Lane 4 0\n", Notation::Numeric, GameRules::default()).unwrap(),
+        ];
+        let standings = team_standings(&scorecards, &roster(), &Variant1);
+        let totals = standings.iter()
+            .map(|standing| (standing.rank, standing.name.as_str(), standing.total, standing.blinds, standing.series.len()))
+            .collect::<Vec<_>>();
+        assert_eq!(totals, [(1, "Pin Pals", 61, 1, 2), (2, "Gutter Gang", 20, 0, 2)]);
+    }
+
+    #[test]
+    fn test_invalid_roster() {
+        let roster: Roster = toml::from_str("\
+            [[teams]]\n\
+            name = \"A\"\n\
+            bowlers = [\"Eve Stojbs\"]\n\
+            [[teams]]\n\
+            name = \"B\"\n\
+            bowlers = [\"Bo Ling\"]\n\
+            substitutes = [\"Eve Stojbs\"]\n\
+            ").unwrap();
+        assert!(roster.validate().is_err());
+        assert!(toml::from_str::<Roster>("[[teams]]\nname = \"A\"\nbowlers = []\ncaptain = \"Eve\"").is_err());
+    }
+}
@@ -0,0 +1,149 @@
+use std::str::FromStr;
+use anyhow::{bail, Error, Result};
+use crate::scorecard::{Scorecard, Series};
+use crate::variants::ScoreCalculator;
+
+/// A rule for ordering bowlers that ended up with the same total
+pub trait TieBreaker {
+    /// How well a bowler did by this rule, higher is better, or `None` if the rule can't rate them
+    fn rate(&self, bowler: &str, games: &[&Series], variant: &dyn ScoreCalculator) -> Option<i64>;
+}
+
+/// The best single game wins
+pub struct HighGame;
+
+impl TieBreaker for HighGame {
+    fn rate(&self, _: &str, games: &[&Series], variant: &dyn ScoreCalculator) -> Option<i64> {
+        games.iter()
+            .map(|series| variant.calculate_score(&series.frames) as i64)
+            .max()
+    }
+}
+
+/// Most strikes over all games wins
+pub struct MostStrikes;
+
+impl TieBreaker for MostStrikes {
+    fn rate(&self, _: &str, games: &[&Series], _: &dyn ScoreCalculator) -> Option<i64> {
+        Some(games.iter()
+            .flat_map(|series| series.frames.iter())
+            .map(|frame| frame.strikes() as i64)
+            .sum())
+    }
+}
+
+/// Fewest open frames over all games wins
+pub struct FewestOpenFrames;
+
+impl TieBreaker for FewestOpenFrames {
+    fn rate(&self, _: &str, games: &[&Series], _: &dyn ScoreCalculator) -> Option<i64> {
+        Some(-(games.iter()
+            .flat_map(|series| series.frames.iter())
+            .filter(|frame| frame.is_open())
+            .count() as i64))
+    }
+}
+
+/// The tied bowlers bowl extra head-to-head frames, recorded on separate scorecards, and the best of those wins
+pub struct RollOff {
+    pub scorecards: Vec<Scorecard>,
+}
+
+impl TieBreaker for RollOff {
+    fn rate(&self, bowler: &str, _: &[&Series], variant: &dyn ScoreCalculator) -> Option<i64> {
+        self.scorecards.iter()
+            .flat_map(|scorecard| scorecard.series.iter())
+            .filter(|series| series.name == bowler)
+            .map(|series| variant.calculate_score(&series.frames) as i64)
+            .reduce(|total, score| total + score)
+    }
+}
+
+/// The built-in tie-break rules, by the name they are selected with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TieBreak {
+    HighGame,
+    MostStrikes,
+    FewestOpenFrames,
+    RollOff,
+}
+
+impl FromStr for TieBreak {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            _ if s.eq_ignore_ascii_case("high-game") => Ok(TieBreak::HighGame),
+            _ if s.eq_ignore_ascii_case("most-strikes") => Ok(TieBreak::MostStrikes),
+            _ if s.eq_ignore_ascii_case("fewest-open-frames") => Ok(TieBreak::FewestOpenFrames),
+            _ if s.eq_ignore_ascii_case("roll-off") => Ok(TieBreak::RollOff),
+            _ => bail!("Invalid tie-break rule {}", s),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::frame::GameRules;
+    use crate::scorecard::{Notation, Scorecard};
+    use crate::standings::standings;
+    use crate::variants::Variant1;
+    use super::{FewestOpenFrames, HighGame, MostStrikes, RollOff, TieBreaker};
+
+    #[test]
+    fn test_tie_breakers() {
+        // Given two bowlers with the same total
+        let scorecards = [Scorecard::parse("league.txt", "\
+            Yattas Del Lana 10 0 0 0 0 0 0\n\
+            Eve Stojbs 3 3\n\
+            Eve Stojbs 2 2\n\
+            ", Notation::Numeric, GameRules::default()).unwrap()];
+        let roll_off = RollOff {
+            scorecards: vec![Scorecard::parse("roll-off.txt", "Yattas Del Lana 7 2\nEve Stojbs 5 5\n", Notation::Numeric, GameRules::default()).unwrap()],
+        };
+
+        for (tie_breaker, expected_winner) in [
+            (&HighGame as &dyn TieBreaker, "Yattas Del Lana"),
+            (&MostStrikes, "Yattas Del Lana"),
+            (&FewestOpenFrames, "Eve Stojbs"),
+            (&roll_off, "Eve Stojbs"),
+        ] {
+            // When the tie is broken
+            let standings = standings(&scorecards, &Variant1, &[tie_breaker]);
+
+            // Then the winner is ranked ahead of the other bowler
+            assert_eq!((standings[0].rank, standings[0].name.as_str()), (1, expected_winner));
+            assert_eq!(standings[1].rank, 2);
+        }
+    }
+
+    #[test]
+    fn test_tie_breakers_in_order() {
+        // Given three bowlers where two are still tied after the first rule
+        let scorecards = [Scorecard::parse("league.txt", "\
+            A 5 5 0 0\n\
+            B 10 0 0\n\
+            C 9 0 0 0\n\
+            ", Notation::Numeric, GameRules::default()).unwrap()];
+
+        // When the first rule can't separate A and B
+        let standings = standings(&scorecards, &Variant1, &[&HighGame, &MostStrikes]);
+
+        // Then the next rule is used
+        let ranks: Vec<_> = standings.iter().map(|standing| (standing.rank, standing.name.as_str())).collect();
+        assert_eq!(ranks, [(1, "B"), (2, "A"), (3, "C")]);
+    }
+
+    #[test]
+    fn test_roll_off_without_all_bowlers() {
+        // Given a roll-off that only one of the tied bowlers took part in
+        let scorecards = [Scorecard::parse("league.txt", "A 5 4\nB 9 0\n", Notation::Numeric, GameRules::default()).unwrap()];
+        let roll_off = RollOff {
+            scorecards: vec![Scorecard::parse("roll-off.txt", "B 9 0\n", Notation::Numeric, GameRules::default()).unwrap()],
+        };
+
+        // Then the tie remains
+        let standings = standings(&scorecards, &Variant1, &[&roll_off]);
+        assert_eq!(standings.iter().map(|standing| standing.rank).collect::<Vec<_>>(), [1, 1]);
+    }
+}
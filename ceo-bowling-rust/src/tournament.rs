@@ -0,0 +1,278 @@
+use std::str::FromStr;
+use anyhow::{bail, Error, Result};
+use crate::scorecard::Scorecard;
+use crate::standings::Standing;
+use crate::variants::ScoreCalculator;
+
+/// How the finals after qualifying are run
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Finals {
+    /// The lowest two seeds bowl first and the winner moves up to meet the next seed, until the top seed
+    Stepladder,
+    /// Single elimination with the top seed meeting the lowest and byes for the top seeds when the field
+    /// isn't a power of two
+    Bracket,
+}
+
+impl FromStr for Finals {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            _ if s.eq_ignore_ascii_case("stepladder") => Ok(Finals::Stepladder),
+            _ if s.eq_ignore_ascii_case("bracket") => Ok(Finals::Bracket),
+            _ => bail!("Invalid finals {}, expected stepladder or bracket", s),
+        }
+    }
+}
+
+/// A finals match once it has been bowled
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MatchOutcome {
+    pub round: usize,
+    pub scorecard: String,
+    /// The bowlers and their totals, the higher seed first
+    pub scores: [(String, u32); 2],
+    pub winner: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum State {
+    Stepladder {
+        /// Seeds still to enter, the top seed first
+        waiting: Vec<String>,
+        /// The higher seed first
+        current: (String, String),
+    },
+    Bracket {
+        /// The matches of the current round, a bowler without an opponent having a bye
+        pairs: Vec<(Option<String>, Option<String>)>,
+        winners: Vec<Option<String>>,
+    },
+    Champion(String),
+}
+
+/// Finals between the seeds from qualifying, advancing winners as match scorecards are recorded
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Tournament {
+    pub seeds: Vec<String>,
+    round: usize,
+    state: State,
+    matches: Vec<MatchOutcome>,
+}
+
+impl Tournament {
+    /// Finals between the seeds, the top seed first
+    pub fn new(finals: Finals, seeds: Vec<String>) -> Result<Tournament> {
+        if seeds.len() < 2 {
+            bail!("Finals need at least two bowlers");
+        }
+        let state = match finals {
+            Finals::Stepladder => {
+                let mut waiting = seeds.clone();
+                let lowest = waiting.pop().unwrap();
+                let next_lowest = waiting.pop().unwrap();
+                State::Stepladder { waiting, current: (next_lowest, lowest) }
+            }
+            Finals::Bracket => {
+                let slots = bracket_order(seeds.len().next_power_of_two());
+                let pairs: Vec<_> = slots.chunks(2)
+                    .map(|pair| (seeds.get(pair[0]).cloned(), seeds.get(pair[1]).cloned()))
+                    .collect();
+                State::Bracket { winners: vec![None; pairs.len()], pairs }
+            }
+        };
+        let mut tournament = Tournament { seeds, round: 1, state, matches: Vec::new() };
+        tournament.advance();
+        Ok(tournament)
+    }
+
+    /// Seed the finals from the top of the qualifying standings
+    pub fn from_standings(finals: Finals, standings: &[Standing], seeds: usize) -> Result<Tournament> {
+        Tournament::new(finals, standings.iter().take(seeds).map(|standing| standing.name.clone()).collect())
+    }
+
+    /// The bowlers of the match to be bowled next, the higher seed first
+    pub fn next_match(&self) -> Option<(&str, &str)> {
+        match &self.state {
+            State::Stepladder { current, .. } => Some((&current.0, &current.1)),
+            State::Bracket { pairs, winners } => pairs.iter()
+                .zip(winners)
+                .find_map(|(pair, winner)| match (pair, winner) {
+                    ((Some(first), Some(second)), None) => Some((first.as_str(), second.as_str())),
+                    _ => None,
+                }),
+            State::Champion(_) => None,
+        }
+    }
+
+    pub fn champion(&self) -> Option<&str> {
+        match &self.state {
+            State::Champion(champion) => Some(champion),
+            _ => None,
+        }
+    }
+
+    /// The round the next match is in, every stepladder match being a round of its own
+    pub fn round(&self) -> usize {
+        self.round
+    }
+
+    pub fn matches(&self) -> &[MatchOutcome] {
+        &self.matches
+    }
+
+    /// Score the next match from its scorecard, adding up each bowler's games on it, and advance the winner. A
+    /// tie isn't recorded and gives none, so a roll-off scorecard can be recorded next.
+    pub fn record(&mut self, scorecard: &Scorecard, variant: &dyn ScoreCalculator) -> Result<Option<&MatchOutcome>> {
+        let Some((first, second)) = self.next_match() else {
+            bail!("The finals are over");
+        };
+        let total = |name: &str| -> Result<u32> {
+            let games: Vec<_> = scorecard.series.iter().filter(|series| series.name == name).collect();
+            if games.is_empty() {
+                bail!("{} is not on scorecard {}", name, scorecard.name);
+            }
+            Ok(games.iter().map(|series| variant.calculate_score(&series.frames)).sum())
+        };
+        let scores = [(first.to_owned(), total(first)?), (second.to_owned(), total(second)?)];
+        if scores[0].1 == scores[1].1 {
+            return Ok(None);
+        }
+        let winner = if scores[0].1 > scores[1].1 { &scores[0].0 } else { &scores[1].0 }.clone();
+        self.matches.push(MatchOutcome { round: self.round, scorecard: scorecard.name.clone(), scores, winner: winner.clone() });
+        match &mut self.state {
+            State::Stepladder { waiting, .. } => {
+                self.state = match waiting.pop() {
+                    Some(seed) => State::Stepladder { waiting: std::mem::take(waiting), current: (seed, winner) },
+                    None => State::Champion(winner),
+                };
+                self.round += 1;
+            }
+            State::Bracket { pairs, winners } => {
+                let index = pairs.iter()
+                    .zip(winners.iter())
+                    .position(|(pair, winner)| pair.0.is_some() && pair.1.is_some() && winner.is_none())
+                    .unwrap();
+                winners[index] = Some(winner);
+                self.advance();
+            }
+            State::Champion(_) => unreachable!(),
+        }
+        Ok(self.matches.last())
+    }
+
+    /// Move byes through the bracket and start the next round once every match of the current one is decided
+    fn advance(&mut self) {
+        let State::Bracket { pairs, winners } = &mut self.state else {
+            return;
+        };
+        for (pair, winner) in pairs.iter().zip(winners.iter_mut()) {
+            if let (Some(bowler), None) | (None, Some(bowler)) = pair {
+                *winner = Some(bowler.clone());
+            }
+        }
+        if winners.iter().any(Option::is_none) {
+            return;
+        }
+        let winners: Vec<_> = winners.iter().flatten().cloned().collect();
+        self.state = match &winners[..] {
+            [champion] => State::Champion(champion.clone()),
+            _ => State::Bracket {
+                pairs: winners.chunks(2).map(|pair| (Some(pair[0].clone()), Some(pair[1].clone()))).collect(),
+                winners: vec![None; winners.len() / 2],
+            },
+        };
+        self.round += 1;
+    }
+}
+
+/// Seed indexes in bracket order for a power of two number of slots, so the top seeds can only meet late
+fn bracket_order(slots: usize) -> Vec<usize> {
+    let mut order = vec![0];
+    while order.len() < slots {
+        let size = order.len() * 2;
+        order = order.iter().flat_map(|seed| [*seed, size - 1 - seed]).collect();
+    }
+    order
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::frame::GameRules;
+    use crate::scorecard::{Notation, Scorecard};
+    use crate::variants::Variant1;
+    use super::{bracket_order, Finals, Tournament};
+
+    fn seeds(count: usize) -> Vec<String> {
+        (1..=count).map(|seed| format!("Seed {}", seed)).collect()
+    }
+
+    /// A scorecard where the first bowler wins
+    fn scorecard(winner: &str, loser: &str) -> Scorecard {
+        Scorecard::parse("match.txt", &format!("\"{}\" 9 0\n\"{}\" 1 0\n", winner, loser), Notation::Numeric, GameRules::default()).unwrap()
+    }
+
+    #[test]
+    fn test_stepladder() {
+        // Given a five bowler stepladder
+        let mut tournament = Tournament::new(Finals::Stepladder, seeds(5)).unwrap();
+
+        // When the lowest seed wins until the final
+        for (expected_match, winner) in [
+            (("Seed 4", "Seed 5"), "Seed 5"),
+            (("Seed 3", "Seed 5"), "Seed 5"),
+            (("Seed 2", "Seed 5"), "Seed 5"),
+            (("Seed 1", "Seed 5"), "Seed 1"),
+        ] {
+            let next_match = tournament.next_match().unwrap();
+            assert_eq!(next_match, expected_match);
+            let loser = if next_match.0 == winner { next_match.1 } else { next_match.0 }.to_owned();
+            tournament.record(&scorecard(winner, &loser), &Variant1).unwrap();
+        }
+
+        // Then the top seed is champion after four rounds
+        assert_eq!(tournament.champion(), Some("Seed 1"));
+        assert_eq!(tournament.matches().iter().map(|outcome| outcome.round).collect::<Vec<_>>(), [1, 2, 3, 4]);
+        assert!(tournament.record(&scorecard("Seed 1", "Seed 2"), &Variant1).is_err());
+    }
+
+    #[test]
+    fn test_bracket() {
+        assert_eq!(bracket_order(8), [0, 7, 3, 4, 1, 6, 2, 5]);
+
+        // Given a bracket of six, where the top two seeds get a bye
+        let mut tournament = Tournament::new(Finals::Bracket, seeds(6)).unwrap();
+
+        // When the higher seed always wins
+        let mut played = Vec::new();
+        while let Some((higher, lower)) = tournament.next_match() {
+            let (higher, lower) = (higher.to_owned(), lower.to_owned());
+            let round = tournament.round();
+            tournament.record(&scorecard(&higher, &lower), &Variant1).unwrap();
+            played.push((round, higher, lower));
+        }
+
+        // Then the seeds meet as in a bracket and the top seed is champion
+        let played: Vec<_> = played.iter().map(|(round, higher, lower)| (*round, higher.as_str(), lower.as_str())).collect();
+        assert_eq!(played, [
+            (1, "Seed 4", "Seed 5"),
+            (1, "Seed 3", "Seed 6"),
+            (2, "Seed 1", "Seed 4"),
+            (2, "Seed 2", "Seed 3"),
+            (3, "Seed 1", "Seed 2"),
+        ]);
+        assert_eq!(tournament.champion(), Some("Seed 1"));
+    }
+
+    #[test]
+    fn test_tied_match() {
+        let mut tournament = Tournament::new(Finals::Stepladder, seeds(2)).unwrap();
+        let tie = Scorecard::parse("final.txt", "\"Seed 1\" 5 0\n\"Seed 2\" 4 1\n", Notation::Numeric, GameRules::default()).unwrap();
+        assert_eq!(tournament.record(&tie, &Variant1).unwrap(), None);
+        assert_eq!(tournament.next_match(), Some(("Seed 1", "Seed 2")));
+        assert!(tournament.record(&scorecard("Seed 3", "Seed 1"), &Variant1).is_err());
+        tournament.record(&scorecard("Seed 2", "Seed 1"), &Variant1).unwrap();
+        assert_eq!(tournament.champion(), Some("Seed 2"));
+    }
+}
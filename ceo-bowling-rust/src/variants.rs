@@ -0,0 +1,367 @@
+use std::rc::Rc;
+use crate::frame::{Frame, TenthFrame};
+
+pub trait ScoreCalculator {
+    fn calculate_score(&self, series: &[Frame]) -> u32;
+
+    /// The score of every frame with a running total, the last of which is the score of the series.
+    ///
+    /// By default the frames are scored one prefix of the series at a time, so a bonus that depends on later frames
+    /// shows up in the frame that completes it rather than the frame that earned it.
+    fn score_frames(&self, series: &[Frame]) -> Vec<FrameScore> {
+        let mut previous = 0;
+        with_running_totals(series, (1..=series.len()).map(|end| {
+            let score = self.calculate_score(&series[..end]);
+            let frame_score = score.saturating_sub(previous);
+            previous = score;
+            let base = frame_score.min(series[end - 1].pins());
+            let bonus = frame_score - base;
+            (base, bonus, if bonus > 0 { BonusSource::Other } else { BonusSource::None })
+        }))
+    }
+
+    /// The named variants this one adds up, if any
+    fn parts(&self) -> Vec<(&str, &dyn ScoreCalculator)> {
+        Vec::new()
+    }
+}
+
+/// How one frame was scored
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FrameScore {
+    pub frame: Frame,
+    /// Points for the pins knocked down in the frame itself
+    pub base: u32,
+    /// Points on top of the base, for a strike, a spare or the rolls after them
+    pub bonus: u32,
+    pub bonus_source: BonusSource,
+    /// Running total up to and including this frame
+    pub total: u32,
+}
+
+/// What a frame earned its bonus for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BonusSource {
+    None,
+    /// A bonus for the strike or spare itself, which may have grown with earlier strikes or spares
+    Fixed,
+    /// The pins of this many following rolls
+    NextRolls(usize),
+    /// Several kinds of bonus, or one the variant doesn't describe
+    Other,
+}
+
+/// Turn the base and bonus of every frame into frame scores with running totals
+pub fn with_running_totals(series: &[Frame], scores: impl IntoIterator<Item = (u32, u32, BonusSource)>) -> Vec<FrameScore> {
+    series.iter()
+        .zip(scores)
+        .scan(0, |total, (frame, (base, bonus, bonus_source))| {
+            *total += base + bonus;
+            Some(FrameScore { frame: *frame, base, bonus, bonus_source, total: *total })
+        })
+        .collect()
+}
+
+/// Add up the frame scores of several variants frame by frame
+pub fn sum_frame_scores(series: &[Frame], variants: &[&dyn ScoreCalculator]) -> Vec<FrameScore> {
+    let scores = variants.iter()
+        .map(|variant| variant.score_frames(series))
+        .collect::<Vec<_>>();
+    with_running_totals(series, (0..series.len()).map(|index| scores.iter()
+        .map(|frame_scores| frame_scores[index])
+        .fold((0, 0, BonusSource::None), |(base, bonus, bonus_source), frame_score| {
+            let bonus_source = match (bonus_source, frame_score.bonus_source) {
+                (bonus_source, BonusSource::None) => bonus_source,
+                (BonusSource::None, frame_bonus_source) => frame_bonus_source,
+                (bonus_source, frame_bonus_source) if bonus_source == frame_bonus_source => bonus_source,
+                _ => BonusSource::Other,
+            };
+            (base + frame_score.base, bonus + frame_score.bonus, bonus_source)
+        })))
+}
+
+#[derive(Default)]
+pub struct Variant1;
+
+impl ScoreCalculator for Variant1 {
+    fn calculate_score(&self, series: &[Frame]) -> u32 {
+        series.iter()
+            .map(|roll| match roll {
+                Frame::Regular(first, second) | Frame::Tenth(TenthFrame::Regular(first, second)) => (first + second) as u32,
+                Frame::Spare(_) | Frame::Strike | Frame::Tenth(_) => 10u32,
+            })
+            .sum()
+    }
+
+    fn score_frames(&self, series: &[Frame]) -> Vec<FrameScore> {
+        with_running_totals(series, series.iter().map(|frame| (frame.pins(), 0, BonusSource::None)))
+    }
+}
+
+pub struct Variant2 {
+    pub spare_bonus: u32,
+    pub strike_bonus: u32,
+}
+
+impl Default for Variant2 {
+    fn default() -> Self {
+        Variant2 {
+            spare_bonus: 5,
+            strike_bonus: 10,
+        }
+    }
+}
+
+impl ScoreCalculator for Variant2 {
+    fn calculate_score(&self, series: &[Frame]) -> u32 {
+        series.iter()
+            .map(|roll| match roll {
+                Frame::Regular(first, second) | Frame::Tenth(TenthFrame::Regular(first, second)) => (first + second) as u32,
+                Frame::Spare(_) | Frame::Tenth(TenthFrame::Spare(_, _)) => 10 + self.spare_bonus,
+                Frame::Strike | Frame::Tenth(TenthFrame::Strike(_, _)) => 10 + self.strike_bonus,
+            })
+            .sum()
+    }
+
+    fn score_frames(&self, series: &[Frame]) -> Vec<FrameScore> {
+        with_running_totals(series, series.iter().map(|frame| match frame {
+            Frame::Regular(_, _) | Frame::Tenth(TenthFrame::Regular(_, _)) => (frame.pins(), 0, BonusSource::None),
+            Frame::Spare(_) | Frame::Tenth(TenthFrame::Spare(_, _)) => (10, self.spare_bonus, BonusSource::Fixed),
+            Frame::Strike | Frame::Tenth(TenthFrame::Strike(_, _)) => (10, self.strike_bonus, BonusSource::Fixed),
+        }))
+    }
+}
+
+pub struct Variant3 {
+    pub spare_bonus: u32,
+    pub spare_increment: u32,
+    pub strike_bonus: u32,
+    pub strike_increment: u32,
+}
+
+impl Default for Variant3 {
+    fn default() -> Self {
+        Variant3 {
+            spare_bonus: 5,
+            spare_increment: 1,
+            strike_bonus: 10,
+            strike_increment: 2,
+        }
+    }
+}
+
+impl ScoreCalculator for Variant3 {
+    fn calculate_score(&self, series: &[Frame]) -> u32 {
+        series.iter()
+            .fold((0u32, self.spare_bonus, self.strike_bonus), |state, frame| {
+                let (score, spare_bonus, strike_bonus) = state;
+                let score = score + match frame {
+                    Frame::Regular(first, second) | Frame::Tenth(TenthFrame::Regular(first, second)) => (first + second) as u32,
+                    Frame::Spare(_) | Frame::Tenth(TenthFrame::Spare(_, _)) => 10 + spare_bonus,
+                    Frame::Strike | Frame::Tenth(TenthFrame::Strike(_, _)) => 10 + strike_bonus,
+                };
+                let (spare_bonus, strike_bonus) = match frame {
+                    Frame::Regular(_, _) | Frame::Tenth(TenthFrame::Regular(_, _)) => (spare_bonus, strike_bonus),
+                    Frame::Spare(_) | Frame::Tenth(TenthFrame::Spare(_, _)) => (spare_bonus + self.spare_increment, strike_bonus),
+                    Frame::Strike | Frame::Tenth(TenthFrame::Strike(_, _)) => (spare_bonus, strike_bonus + self.strike_increment),
+                };
+                (score, spare_bonus, strike_bonus)
+            }).0
+    }
+
+    fn score_frames(&self, series: &[Frame]) -> Vec<FrameScore> {
+        with_running_totals(series, series.iter()
+            .scan((self.spare_bonus, self.strike_bonus), |(spare_bonus, strike_bonus), frame| Some(match frame {
+                Frame::Regular(_, _) | Frame::Tenth(TenthFrame::Regular(_, _)) => (frame.pins(), 0, BonusSource::None),
+                Frame::Spare(_) | Frame::Tenth(TenthFrame::Spare(_, _)) => {
+                    *spare_bonus += self.spare_increment;
+                    (10, *spare_bonus - self.spare_increment, BonusSource::Fixed)
+                }
+                Frame::Strike | Frame::Tenth(TenthFrame::Strike(_, _)) => {
+                    *strike_bonus += self.strike_increment;
+                    (10, *strike_bonus - self.strike_increment, BonusSource::Fixed)
+                }
+            })))
+    }
+}
+
+#[derive(Default)]
+pub struct Variant4 {}
+
+impl ScoreCalculator for Variant4 {
+    fn calculate_score(&self, series: &[Frame]) -> u32 {
+        series.iter()
+            .rev()
+            .fold((0u32, 0u8, 0u8), |state, frame| {
+                let (score, next_roll, second_next_roll) = state;
+                let score = score + match frame {
+                    Frame::Regular(first, second) | Frame::Tenth(TenthFrame::Regular(first, second)) => (first + second) as u32,
+                    Frame::Spare(_) => (10 + next_roll) as u32,
+                    Frame::Strike => (10 + next_roll + second_next_roll) as u32,
+                    Frame::Tenth(TenthFrame::Spare(_, fill)) => (10 + fill) as u32,
+                    Frame::Tenth(TenthFrame::Strike(first_fill, second_fill)) => (10 + first_fill + second_fill) as u32,
+                };
+                let (next_roll, second_next_roll) = match frame {
+                    Frame::Regular(first, second) | Frame::Tenth(TenthFrame::Regular(first, second)) => (*first, *second),
+                    Frame::Spare(first) | Frame::Tenth(TenthFrame::Spare(first, _)) => (*first, 10 - first),
+                    Frame::Strike => (10, next_roll),
+                    Frame::Tenth(TenthFrame::Strike(first_fill, _)) => (10, *first_fill),
+                };
+                (score, next_roll, second_next_roll)
+            }).0
+    }
+
+    fn score_frames(&self, series: &[Frame]) -> Vec<FrameScore> {
+        let mut scores = series.iter()
+            .rev()
+            .scan((0u8, 0u8), |(next_roll, second_next_roll), frame| {
+                let score = match frame {
+                    Frame::Regular(_, _) | Frame::Tenth(TenthFrame::Regular(_, _)) => (frame.pins(), 0, BonusSource::None),
+                    Frame::Spare(_) => (10, *next_roll as u32, BonusSource::NextRolls(1)),
+                    Frame::Strike => (10, (*next_roll + *second_next_roll) as u32, BonusSource::NextRolls(2)),
+                    Frame::Tenth(TenthFrame::Spare(_, fill)) => (10, *fill as u32, BonusSource::NextRolls(1)),
+                    Frame::Tenth(TenthFrame::Strike(first_fill, second_fill)) => (10, (first_fill + second_fill) as u32, BonusSource::NextRolls(2)),
+                };
+                (*next_roll, *second_next_roll) = match frame {
+                    Frame::Strike => (10, *next_roll),
+                    Frame::Tenth(TenthFrame::Strike(first_fill, _)) => (10, *first_fill),
+                    _ => {
+                        let rolls = frame.rolls();
+                        (rolls[0], rolls[1])
+                    }
+                };
+                Some(score)
+            })
+            .collect::<Vec<_>>();
+        scores.reverse();
+        with_running_totals(series, scores)
+    }
+}
+
+#[derive(Default)]
+pub struct Variant5 {
+    pub variant1: Variant1,
+    pub variant2: Variant2,
+    pub variant3: Variant3,
+    pub variant4: Variant4,
+}
+
+impl ScoreCalculator for Variant5 {
+    fn calculate_score(&self, series: &[Frame]) -> u32 {
+        let variants: &[&dyn ScoreCalculator] = &[&self.variant1, &self.variant2, &self.variant3, &self.variant4];
+            variants.iter()
+            .map(|variant| variant.calculate_score(series))
+            .sum()
+    }
+
+    fn score_frames(&self, series: &[Frame]) -> Vec<FrameScore> {
+        sum_frame_scores(series, &[&self.variant1, &self.variant2, &self.variant3, &self.variant4])
+    }
+
+    fn parts(&self) -> Vec<(&str, &dyn ScoreCalculator)> {
+        vec![
+            ("variant1", &self.variant1),
+            ("variant2", &self.variant2),
+            ("variant3", &self.variant3),
+            ("variant4", &self.variant4),
+        ]
+    }
+}
+
+impl<T: ScoreCalculator + ?Sized> ScoreCalculator for Box<T> {
+    fn calculate_score(&self, series: &[Frame]) -> u32 {
+        self.as_ref().calculate_score(series)
+    }
+
+    fn score_frames(&self, series: &[Frame]) -> Vec<FrameScore> {
+        self.as_ref().score_frames(series)
+    }
+
+    fn parts(&self) -> Vec<(&str, &dyn ScoreCalculator)> {
+        self.as_ref().parts()
+    }
+}
+
+impl<T: ScoreCalculator + ?Sized> ScoreCalculator for Rc<T> {
+    fn calculate_score(&self, series: &[Frame]) -> u32 {
+        self.as_ref().calculate_score(series)
+    }
+
+    fn score_frames(&self, series: &[Frame]) -> Vec<FrameScore> {
+        self.as_ref().score_frames(series)
+    }
+
+    fn parts(&self) -> Vec<(&str, &dyn ScoreCalculator)> {
+        self.as_ref().parts()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::frame::{Frame, GameRules, TenthFrame};
+    use crate::registry::Registry;
+    use crate::scorecard::{parse_line, Notation};
+    use super::{BonusSource, FrameScore, ScoreCalculator, Variant3, Variant4};
+
+    #[test]
+    fn test_score_frames_adds_up() {
+        let registry = Registry::default();
+        for line in [
+            "Yattas Del Lana 3 5 3 5 7 2 3 0 10 4 3",
+            "Eve Stojbs 3 7 3 3 9 1 6 4 2 3 1 0",
+            "Perfect Game 10 10 10 10 10 10 10 10 10 10 10 10",
+            "Bo Ling 10 7 3 9 0 0 8 10 10 8 1 8 2 0 0 9 1 10",
+        ] {
+            let (_, series) = parse_line(line, Notation::Numeric, GameRules::default()).unwrap();
+            for entry in registry.entries() {
+                // Given the frame scores of a series
+                let frame_scores = entry.variant.score_frames(&series);
+
+                // Expect the running total to end at the score of the series
+                assert_eq!(frame_scores.len(), series.len());
+                assert_eq!(frame_scores.last().unwrap().total, entry.variant.calculate_score(&series), "{} {}", entry.name, line);
+            }
+        }
+    }
+
+    #[test]
+    fn test_score_frames_variant3() {
+        let series = [Frame::Strike, Frame::Spare(3), Frame::Strike, Frame::Regular(1, 2)];
+        assert_eq!(Variant3::default().score_frames(&series), [
+            FrameScore { frame: Frame::Strike, base: 10, bonus: 10, bonus_source: BonusSource::Fixed, total: 20 },
+            FrameScore { frame: Frame::Spare(3), base: 10, bonus: 5, bonus_source: BonusSource::Fixed, total: 35 },
+            FrameScore { frame: Frame::Strike, base: 10, bonus: 12, bonus_source: BonusSource::Fixed, total: 57 },
+            FrameScore { frame: Frame::Regular(1, 2), base: 3, bonus: 0, bonus_source: BonusSource::None, total: 60 },
+        ]);
+    }
+
+    #[test]
+    fn test_score_frames_variant4() {
+        let mut series = vec![Frame::Regular(0, 0); 7];
+        series.extend([Frame::Strike, Frame::Spare(7), Frame::Tenth(TenthFrame::Strike(10, 4))]);
+        let frame_scores = Variant4::default().score_frames(&series);
+        assert_eq!(frame_scores[7..], [
+            FrameScore { frame: Frame::Strike, base: 10, bonus: 10, bonus_source: BonusSource::NextRolls(2), total: 20 },
+            FrameScore { frame: Frame::Spare(7), base: 10, bonus: 10, bonus_source: BonusSource::NextRolls(1), total: 40 },
+            FrameScore { frame: Frame::Tenth(TenthFrame::Strike(10, 4)), base: 10, bonus: 14, bonus_source: BonusSource::NextRolls(2), total: 64 },
+        ]);
+    }
+
+    #[test]
+    fn test_score_frames_default() {
+        // Given a variant that only implements the total score
+        struct Doubled;
+
+        impl ScoreCalculator for Doubled {
+            fn calculate_score(&self, series: &[Frame]) -> u32 {
+                series.iter().map(|frame| frame.pins() * 2).sum()
+            }
+        }
+
+        // Expect the frame scores to be worked out from the total
+        assert_eq!(Doubled.score_frames(&[Frame::Regular(1, 2), Frame::Strike]), [
+            FrameScore { frame: Frame::Regular(1, 2), base: 3, bonus: 3, bonus_source: BonusSource::Other, total: 6 },
+            FrameScore { frame: Frame::Strike, base: 10, bonus: 10, bonus_source: BonusSource::Other, total: 26 },
+        ]);
+    }
+}